@@ -0,0 +1,189 @@
+use std::cell::RefCell;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::object::{Builtin, Null, Object, ObjectType};
+
+use super::capabilities::{Capabilities, Capability};
+use super::Error;
+
+/// The builtins every evaluator starts with. Each checks `capabilities`
+/// when it is called, before it has any effect.
+pub(super) fn standard(capabilities: &Rc<RefCell<Capabilities>>) -> Vec<Builtin> {
+    let allowed = capabilities.clone();
+    let puts = Builtin::new("puts", move |arguments| {
+        allowed.borrow().check(Capability::Stdout)?;
+        for argument in arguments {
+            println!("{}", argument.inspect());
+        }
+        Ok(Object::Null(Null))
+    });
+
+    let allowed = capabilities.clone();
+    let read_file = Builtin::typed("read_file", move |path: String| {
+        allowed.borrow().check_read(Path::new(&path))?;
+        fs::read_to_string(&path)
+            .map_err(|error| Error::new(format!("cannot read {path:?}: {error}")))
+    });
+
+    let allowed = capabilities.clone();
+    let write_file = Builtin::typed("write_file", move |path: String, contents: String| {
+        allowed.borrow().check_write(Path::new(&path))?;
+        fs::write(&path, contents)
+            .map_err(|error| Error::new(format!("cannot write {path:?}: {error}")))
+    });
+
+    let allowed = capabilities.clone();
+    let clock = Builtin::typed("clock", move || {
+        allowed.borrow().check(Capability::Clock)?;
+        let elapsed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|error| Error::new(format!("clock is before 1970: {error}")))?;
+        Ok(elapsed.as_millis() as i64)
+    });
+
+    let env = env(capabilities, |name| std::env::var(name).ok());
+
+    vec![puts, read_file, write_file, clock, env]
+}
+
+/// The `env` builtin, reading variables through `lookup` so tests need not
+/// change the process environment.
+fn env(
+    capabilities: &Rc<RefCell<Capabilities>>,
+    lookup: impl Fn(&str) -> Option<String> + 'static,
+) -> Builtin {
+    let allowed = capabilities.clone();
+    Builtin::typed("env", move |name: String| {
+        allowed.borrow().check_env(&name)?;
+        Ok(lookup(&name))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+
+    use crate::object::ObjectType;
+
+    use super::super::tests::eval_with as eval;
+    use super::super::{Capabilities, Evaluator, PathAccess};
+    use super::env;
+
+    fn eval_with(capabilities: Capabilities, input: &str) -> Result<String, String> {
+        let mut evaluator = Evaluator::new();
+        evaluator.set_capabilities(capabilities);
+
+        eval(&mut evaluator, input)
+            .map(|value| value.inspect())
+            .map_err(|error| error.message)
+    }
+
+    #[test]
+    fn builtins_need_capabilities() {
+        // Arrange
+        let tests = vec![
+            ("puts(1)", "permission denied: stdout access"),
+            ("clock()", "permission denied: clock access"),
+            ("env(\"HOME\")", "permission denied: env access to \"HOME\""),
+            (
+                "read_file(\"Cargo.toml\")",
+                "permission denied: read access to \"Cargo.toml\"",
+            ),
+            (
+                "write_file(\"out.txt\", \"x\")",
+                "permission denied: write access to \"out.txt\"",
+            ),
+        ];
+
+        for (input, expected) in tests {
+            // Act
+            let result = eval_with(Capabilities::default(), input);
+
+            // Assert
+            assert_eq!(result, Err(expected.to_string()), "{input}");
+        }
+    }
+
+    #[test]
+    fn file_access_is_limited_to_allowed_paths() {
+        // Arrange
+        let temp = tempfile::tempdir().unwrap();
+        let directory = temp.path();
+        let data = directory.join("data");
+        fs::create_dir_all(&data).unwrap();
+        fs::write(data.join("in.txt"), "hello").unwrap();
+        fs::write(directory.join("secret.txt"), "secret").unwrap();
+        let capabilities = Capabilities {
+            read: PathAccess::Only(vec![data.clone()]),
+            write: PathAccess::Only(vec![data.clone()]),
+            ..Capabilities::default()
+        };
+        let path = |name: &str| format!("{:?}", data.join(name).display().to_string());
+        let tests = vec![
+            (
+                format!("read_file({})", path("in.txt")),
+                Ok("hello".to_string()),
+            ),
+            (
+                format!(
+                    "write_file({}, \"hi\"); read_file({})",
+                    path("out.txt"),
+                    path("out.txt")
+                ),
+                Ok("hi".to_string()),
+            ),
+            (
+                format!("read_file({})", path("../secret.txt")),
+                Err(format!(
+                    "permission denied: read access to {}",
+                    path("../secret.txt")
+                )),
+            ),
+            (
+                format!("write_file({}, \"x\")", path("../new.txt")),
+                Err(format!(
+                    "permission denied: write access to {}",
+                    path("../new.txt")
+                )),
+            ),
+        ];
+
+        for (input, expected) in tests {
+            // Act
+            let result = eval_with(capabilities.clone(), &input);
+
+            // Assert
+            assert_eq!(result, expected, "{input}");
+        }
+        assert!(!directory.join("new.txt").exists());
+    }
+
+    #[test]
+    fn allowed_builtins_run() {
+        // Arrange
+        let mut evaluator = Evaluator::new();
+        evaluator.set_capabilities(Capabilities::all());
+        let variables = HashMap::from([("GIBBON_TEST_VALUE", "42")]);
+        evaluator.define_builtin(env(&evaluator.capabilities, move |name| {
+            variables.get(name).map(|value| value.to_string())
+        }));
+        let tests = vec![
+            ("puts(\"hi\", 1)", "null"),
+            ("clock() > 0", "true"),
+            ("env(\"GIBBON_TEST_VALUE\")", "42"),
+            ("env(\"GIBBON_TEST_UNSET\")", "null"),
+        ];
+
+        for (input, expected) in tests {
+            // Act
+            let result = eval(&mut evaluator, input).map(|value| value.inspect());
+
+            // Assert
+            assert_eq!(result.unwrap(), expected, "{input}");
+        }
+    }
+}
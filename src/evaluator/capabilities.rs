@@ -0,0 +1,144 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use super::{Error, Result};
+
+/// A kind of side effect that builtins must be allowed to have.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Capability {
+    Stdout,
+    Read,
+    Write,
+    Clock,
+    Env,
+    Import,
+}
+
+impl Display for Capability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Capability::Stdout => "stdout",
+            Capability::Read => "read",
+            Capability::Write => "write",
+            Capability::Clock => "clock",
+            Capability::Env => "env",
+            Capability::Import => "import",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Which files a builtin may touch.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum PathAccess {
+    #[default]
+    None,
+    All,
+    /// Only files inside these directories, or these files themselves.
+    Only(Vec<PathBuf>),
+}
+
+/// The side effects scripts are allowed to have, including which modules
+/// they may import. Nothing is allowed by default.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Capabilities {
+    pub stdout: bool,
+    pub read: PathAccess,
+    pub write: PathAccess,
+    pub clock: bool,
+    pub env: bool,
+    pub import: PathAccess,
+}
+
+impl Capabilities {
+    pub fn all() -> Self {
+        Capabilities {
+            stdout: true,
+            read: PathAccess::All,
+            write: PathAccess::All,
+            clock: true,
+            env: true,
+            import: PathAccess::All,
+        }
+    }
+
+    /// Fails with a runtime error unless `capability` is allowed. Read and
+    /// write access, and imports, count as allowed when any path is.
+    pub fn check(&self, capability: Capability) -> Result<()> {
+        let allowed = match capability {
+            Capability::Stdout => self.stdout,
+            Capability::Read => self.read != PathAccess::None,
+            Capability::Write => self.write != PathAccess::None,
+            Capability::Clock => self.clock,
+            Capability::Env => self.env,
+            Capability::Import => self.import != PathAccess::None,
+        };
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(denied(capability, None))
+        }
+    }
+
+    pub fn check_read(&self, path: &Path) -> Result<()> {
+        check_path(&self.read, Capability::Read, path)
+    }
+
+    pub fn check_write(&self, path: &Path) -> Result<()> {
+        check_path(&self.write, Capability::Write, path)
+    }
+
+    pub fn check_import(&self, path: &Path) -> Result<()> {
+        check_path(&self.import, Capability::Import, path)
+    }
+
+    pub fn check_env(&self, name: &str) -> Result<()> {
+        if self.env {
+            Ok(())
+        } else {
+            Err(denied(Capability::Env, Some(name)))
+        }
+    }
+}
+
+fn check_path(access: &PathAccess, capability: Capability, path: &Path) -> Result<()> {
+    let allowed = match access {
+        PathAccess::None => false,
+        PathAccess::All => true,
+        PathAccess::Only(roots) => {
+            let path = resolve(path);
+            roots.iter().any(|root| path.starts_with(resolve(root)))
+        }
+    };
+
+    if allowed {
+        Ok(())
+    } else {
+        Err(denied(capability, Some(&path.display().to_string())))
+    }
+}
+
+/// Makes `path` absolute and resolves `..` and symbolic links as far as
+/// the path exists, so a path cannot escape an allowed directory.
+fn resolve(path: &Path) -> PathBuf {
+    if let Ok(path) = path.canonicalize() {
+        return path;
+    }
+
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => resolve(parent).join(name),
+        _ => std::env::current_dir()
+            .map(|cwd| cwd.join(path))
+            .unwrap_or_else(|_| path.to_path_buf()),
+    }
+}
+
+fn denied(capability: Capability, target: Option<&str>) -> Error {
+    match target {
+        Some(target) => Error::new(format!(
+            "permission denied: {capability} access to {target:?}"
+        )),
+        None => Error::new(format!("permission denied: {capability} access")),
+    }
+}
@@ -0,0 +1,160 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+
+use crate::object::{Hash, HashKey, Object, ObjectType, Str, Value};
+use crate::token::Position;
+
+use super::limits::Limit;
+use super::module::display_path;
+
+#[derive(Clone, Debug)]
+pub struct Error {
+    pub message: String,
+    pub kind: ErrorKind,
+    /// The calls in progress when the error happened, innermost first.
+    pub stack: Vec<Frame>,
+    /// The value given to `throw`, for errors the script raised itself.
+    pub thrown: Option<Box<Value>>,
+}
+
+/// A call in progress: the function called, and where it was called from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    pub function: String,
+    /// The file the call is in, unless it was evaluated from a string.
+    pub file: Option<PathBuf>,
+    pub position: Position,
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", display_path(file))?;
+        }
+        write!(f, "{} in {}", self.position, self.function)
+    }
+}
+
+/// What kind of failure an error is.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ErrorKind {
+    /// The script did something invalid.
+    Runtime,
+    /// The script ran into one of the evaluator's limits. Scripts cannot
+    /// catch these.
+    LimitExceeded(Limit),
+    /// The script raised the error with `throw`.
+    Thrown,
+}
+
+impl Error {
+    pub fn new(message: impl Into<String>) -> Error {
+        Error {
+            message: message.into(),
+            kind: ErrorKind::Runtime,
+            stack: vec![],
+            thrown: None,
+        }
+    }
+
+    /// An error raised by `throw value`. Its message is the value if that
+    /// is a string, or the hash's `"message"` if it has a string one.
+    pub(crate) fn thrown(value: Object) -> Error {
+        let message = match &value {
+            Object::Str(Str(message)) => Some(message.clone()),
+            Object::Hash(Hash(pairs)) => match pairs.get(&HashKey::Str("message".to_string())) {
+                Some(Object::Str(Str(message))) => Some(message.clone()),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        Error {
+            message: message.unwrap_or_else(|| value.inspect()),
+            kind: ErrorKind::Thrown,
+            stack: vec![],
+            thrown: Some(Box::new(Value(value))),
+        }
+    }
+
+    pub fn limit_exceeded(limit: Limit, message: impl Into<String>) -> Error {
+        Error {
+            message: format!("limit exceeded: {}", message.into()),
+            kind: ErrorKind::LimitExceeded(limit),
+            stack: vec![],
+            thrown: None,
+        }
+    }
+
+    /// The value a `catch` block receives: what was thrown, or for other
+    /// errors a hash of their `"message"` and `"stack"`.
+    pub fn to_object(&self) -> Object {
+        if let Some(value) = &self.thrown {
+            return value.0.clone();
+        }
+
+        let stack = self
+            .stack
+            .iter()
+            .map(|frame| Object::Str(Str(frame.to_string())))
+            .collect();
+        let pairs = [
+            ("message", Object::Str(Str(self.message.clone()))),
+            ("stack", Object::Array(stack)),
+        ];
+
+        Object::Hash(
+            pairs
+                .into_iter()
+                .map(|(key, value)| (HashKey::Str(key.to_string()), value))
+                .collect(),
+        )
+    }
+
+    /// The stack formatted like a Python traceback, outermost call first,
+    /// or an empty string if the error happened outside any call. Runs of
+    /// the same frame, as in deep recursion, are shortened.
+    pub fn traceback(&self) -> String {
+        if self.stack.is_empty() {
+            return String::new();
+        }
+
+        let mut lines = vec!["Traceback (most recent call last):".to_string()];
+        let mut frames = self.stack.iter().rev().peekable();
+        while let Some(frame) = frames.next() {
+            lines.push(format!("  {frame}"));
+
+            let mut repeated = 0;
+            while frames.next_if_eq(&frame).is_some() {
+                repeated += 1;
+            }
+            match repeated {
+                0 => {}
+                1 => lines.push(format!("  {frame}")),
+                _ => lines.push(format!("  [previous frame repeated {repeated} more times]")),
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+// Objects cannot be compared directly, so thrown values are compared by
+// how they print.
+impl PartialEq for Error {
+    fn eq(&self, other: &Self) -> bool {
+        self.message == other.message
+            && self.kind == other.kind
+            && self.stack == other.stack
+            && self.thrown.as_ref().map(|value| value.to_string())
+                == other.thrown.as_ref().map(|value| value.to_string())
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,297 @@
+use std::fmt::Display;
+
+use crate::object::{Array, BigInteger, Hash, Object, Str};
+
+use super::{Error, Evaluator, Result};
+
+/// Bounds on how much work a single run may do, for running untrusted
+/// scripts. Each run, one call to `eval_program` or `eval_file`, starts
+/// from zero usage.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Limits {
+    /// How many expressions and statements may be evaluated.
+    pub fuel: Option<u64>,
+    /// How deeply function calls may nest.
+    pub call_depth: Option<usize>,
+    /// How much data may be created, counting each byte of a string or big
+    /// integer and each element of an array or hash.
+    pub allocation: Option<usize>,
+}
+
+/// Calls nest up to this deep unless configured otherwise, so runaway
+/// recursion fails with an error.
+pub const DEFAULT_CALL_DEPTH: usize = 10_000;
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            fuel: None,
+            call_depth: Some(DEFAULT_CALL_DEPTH),
+            allocation: None,
+        }
+    }
+}
+
+impl Limits {
+    /// No limits at all, not even on call depth.
+    pub fn none() -> Self {
+        Limits {
+            fuel: None,
+            call_depth: None,
+            allocation: None,
+        }
+    }
+}
+
+/// Which limit a run exceeded.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Limit {
+    Fuel,
+    CallDepth,
+    Allocation,
+}
+
+impl Display for Limit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Limit::Fuel => "fuel",
+            Limit::CallDepth => "call depth",
+            Limit::Allocation => "allocation",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// What the current run has used so far.
+#[derive(Default)]
+pub(super) struct Usage {
+    fuel: u64,
+    pub(super) call_depth: usize,
+    allocation: usize,
+}
+
+impl Evaluator {
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    /// Starts counting fuel and allocation again for a new run.
+    pub(super) fn reset_usage(&mut self) {
+        self.usage = Usage::default();
+    }
+
+    /// Spends one unit of fuel.
+    pub(super) fn tick(&mut self) -> Result<()> {
+        self.usage.fuel += 1;
+        match self.limits.fuel {
+            Some(fuel) if self.usage.fuel > fuel => Err(Error::limit_exceeded(
+                Limit::Fuel,
+                format!("ran out of fuel after {fuel} steps"),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    pub(super) fn enter_call(&mut self) -> Result<()> {
+        match self.limits.call_depth {
+            Some(depth) if self.usage.call_depth >= depth => Err(Error::limit_exceeded(
+                Limit::CallDepth,
+                format!("more than {depth} nested calls"),
+            )),
+            _ => {
+                self.usage.call_depth += 1;
+                Ok(())
+            }
+        }
+    }
+
+    pub(super) fn exit_call(&mut self) {
+        self.usage.call_depth -= 1;
+    }
+
+    /// Records that `value` was newly created.
+    pub(super) fn allocate(&mut self, value: &Object) -> Result<()> {
+        self.allocate_elements(size(value))
+    }
+
+    /// Records that `count` elements were newly created.
+    pub(super) fn allocate_elements(&mut self, count: usize) -> Result<()> {
+        self.usage.allocation += count;
+        match self.limits.allocation {
+            Some(allocation) if self.usage.allocation > allocation => Err(Error::limit_exceeded(
+                Limit::Allocation,
+                format!("allocated more than {allocation} elements"),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn size(value: &Object) -> usize {
+    match value {
+        Object::Str(Str(string)) => string.len(),
+        Object::BigInteger(BigInteger(integer)) => integer.bits().div_ceil(8) as usize,
+        Object::Array(Array(elements)) => elements.len(),
+        Object::Hash(Hash(pairs)) => pairs.len(),
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::object::ObjectType;
+
+    use super::super::tests::eval_with;
+    use super::super::{Error, ErrorKind, Evaluator};
+    use super::{Limit, Limits};
+
+    fn eval_limited(limits: Limits, input: &str) -> Result<String, Error> {
+        let mut evaluator = Evaluator::new();
+        evaluator.set_limits(limits);
+
+        eval_with(&mut evaluator, input).map(|value| value.inspect())
+    }
+
+    #[test]
+    fn limits_stop_runaway_scripts() {
+        // Arrange
+        let tests = vec![
+            (
+                Limits::default(),
+                "let f = fn() { f() }; f()",
+                Limit::CallDepth,
+                "limit exceeded: more than 10000 nested calls",
+            ),
+            (
+                Limits {
+                    call_depth: Some(10),
+                    ..Limits::default()
+                },
+                "fn down(n) { n == 0 ? 0 : down(n - 1) } down(10)",
+                Limit::CallDepth,
+                "limit exceeded: more than 10 nested calls",
+            ),
+            (
+                Limits {
+                    fuel: Some(1000),
+                    ..Limits::default()
+                },
+                "while (true) {}",
+                Limit::Fuel,
+                "limit exceeded: ran out of fuel after 1000 steps",
+            ),
+            (
+                Limits {
+                    fuel: Some(1000),
+                    ..Limits::default()
+                },
+                "for (i in 0..1000000000000) {}",
+                Limit::Fuel,
+                "limit exceeded: ran out of fuel after 1000 steps",
+            ),
+            (
+                Limits {
+                    fuel: Some(3),
+                    ..Limits::default()
+                },
+                "match (5) { 1 => 1, 2 => 2 }",
+                Limit::Fuel,
+                "limit exceeded: ran out of fuel after 3 steps",
+            ),
+            (
+                Limits {
+                    fuel: Some(1000),
+                    ..Limits::default()
+                },
+                "fn f(...xs) { xs } f(...0..1000000000000)",
+                Limit::Fuel,
+                "limit exceeded: ran out of fuel after 1000 steps",
+            ),
+            (
+                Limits {
+                    allocation: Some(100),
+                    ..Limits::default()
+                },
+                "fn f(...xs) { xs } f(...0..1000000000000)",
+                Limit::Allocation,
+                "limit exceeded: allocated more than 100 elements",
+            ),
+            (
+                Limits {
+                    fuel: Some(1000),
+                    ..Limits::default()
+                },
+                "try { while (true) {} } catch (e) { 1 }",
+                Limit::Fuel,
+                "limit exceeded: ran out of fuel after 1000 steps",
+            ),
+            (
+                Limits {
+                    allocation: Some(100),
+                    ..Limits::default()
+                },
+                "let mut s = \"\"; while (true) { s += \"abc\" }",
+                Limit::Allocation,
+                "limit exceeded: allocated more than 100 elements",
+            ),
+            (
+                Limits {
+                    allocation: Some(100),
+                    ..Limits::default()
+                },
+                "let mut h = {}; for (i in 0..1000) { h[i] = [i] }",
+                Limit::Allocation,
+                "limit exceeded: allocated more than 100 elements",
+            ),
+        ];
+
+        for (limits, input, limit, expected) in tests {
+            // Act
+            let error = eval_limited(limits, input).unwrap_err();
+
+            // Assert
+            assert_eq!(error.kind, ErrorKind::LimitExceeded(limit), "{input}");
+            assert_eq!(error.message, expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn scripts_within_limits_run() {
+        // Arrange
+        let limits = Limits {
+            fuel: Some(200),
+            call_depth: Some(10),
+            allocation: Some(10),
+        };
+
+        // Act
+        let result = eval_limited(limits, "fn down(n) { n == 0 ? [n] : down(n - 1) } down(9)");
+        let unlimited = eval_limited(
+            Limits::none(),
+            "fn down(n) { n == 0 ? 0 : down(n - 1) } down(20000)",
+        );
+
+        // Assert
+        assert_eq!(result.unwrap(), "[0]");
+        assert_eq!(unlimited.unwrap(), "0");
+    }
+
+    #[test]
+    fn usage_resets_between_runs() {
+        // Arrange
+        let mut evaluator = Evaluator::new();
+        evaluator.set_limits(Limits {
+            fuel: Some(50),
+            ..Limits::default()
+        });
+        let input = "let mut i = 0; while (i < 5) { i += 1 }";
+
+        // Act
+        let results = (0..3)
+            .map(|_| eval_with(&mut evaluator, input))
+            .collect::<Vec<_>>();
+
+        // Assert
+        assert!(results.iter().all(Result::is_ok), "{results:?}");
+    }
+}
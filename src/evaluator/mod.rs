@@ -0,0 +1,2097 @@
+mod builtins;
+mod capabilities;
+mod error;
+mod limits;
+mod module;
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use num_bigint::BigInt;
+use num_traits::{ToPrimitive, Zero};
+
+use crate::object::{
+    Array, AssignError, BigInteger, Boolean, Builtin, Env, Environment, Float, Function, Hash,
+    HashKey, Integer, Module, Null, Object, ObjectType, Range, Str,
+};
+use crate::parser::ast::{BlockStatement, Expression, Parameter, Pattern, Program, Statement};
+
+pub use capabilities::{Capabilities, Capability, PathAccess};
+pub use error::{Error, ErrorKind, Frame};
+pub use limits::{Limit, Limits, DEFAULT_CALL_DEPTH};
+
+use limits::Usage;
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Why evaluating a statement or expression stopped before producing a
+/// value: an error, or a `return`, `break` or `continue` on its way out to
+/// the function or loop that handles it.
+enum Unwind {
+    Error(Error),
+    Return(Object),
+    Break,
+    Continue,
+}
+
+impl From<Error> for Unwind {
+    fn from(error: Error) -> Self {
+        Unwind::Error(error)
+    }
+}
+
+/// The outcome of evaluating code that can `return`, `break` or `continue`.
+type Flow<T> = std::result::Result<T, Unwind>;
+
+/// A call that starts with less stack than this left moves to a new segment
+/// of `STACK_SEGMENT` bytes.
+const STACK_RED_ZONE: usize = 1024 * 1024;
+const STACK_SEGMENT: usize = 8 * 1024 * 1024;
+
+/// What integer arithmetic does when its result does not fit in an `i64`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Overflow {
+    /// Fail with a runtime error.
+    #[default]
+    Error,
+    /// Continue with an arbitrary-precision integer.
+    Promote,
+}
+
+/// Tree-walking evaluator. Top level bindings live in its global
+/// environment, so they persist between calls to `eval_program`.
+pub struct Evaluator {
+    /// Builtins, enclosing both the global environment and every module's.
+    builtins: Env,
+    /// Globals set by the host, enclosing the global environment.
+    host: Env,
+    env: Env,
+    overflow: Overflow,
+    /// Modules that finished loading, keyed by canonical path.
+    modules: HashMap<PathBuf, Module>,
+    /// Files currently being evaluated, innermost last.
+    loading: Vec<PathBuf>,
+    /// The file of the code being evaluated, for stack traces.
+    file: Option<Rc<PathBuf>>,
+    /// Parser warnings from files read since they were last taken.
+    warnings: Vec<String>,
+    limits: Limits,
+    usage: Usage,
+    /// Shared with the builtins, which check it when called.
+    capabilities: Rc<RefCell<Capabilities>>,
+}
+
+impl Evaluator {
+    pub fn new() -> Self {
+        let builtins = Environment::new();
+        let host = Environment::enclosed(&builtins);
+        let capabilities = Rc::new(RefCell::new(Capabilities::default()));
+
+        let mut evaluator = Evaluator {
+            env: Environment::enclosed(&host),
+            builtins,
+            host,
+            overflow: Overflow::default(),
+            modules: HashMap::new(),
+            loading: Vec::new(),
+            file: None,
+            warnings: Vec::new(),
+            limits: Limits::default(),
+            usage: Usage::default(),
+            capabilities: capabilities.clone(),
+        };
+        for builtin in builtins::standard(&capabilities) {
+            evaluator.define_builtin(builtin);
+        }
+
+        evaluator
+    }
+
+    pub fn set_overflow(&mut self, overflow: Overflow) {
+        self.overflow = overflow;
+    }
+
+    /// Binds `name` in a scope enclosing the global environment. Scripts
+    /// cannot assign to it, but their own `let` of the name shadows it.
+    pub fn set_global(&mut self, name: impl Into<String>, value: Object) {
+        self.host.borrow_mut().set(name.into(), value);
+    }
+
+    /// Makes `builtin` callable under its name from scripts and the modules
+    /// they import.
+    pub fn define_builtin(&mut self, builtin: Builtin) {
+        self.builtins
+            .borrow_mut()
+            .set(builtin.name.clone(), Object::Builtin(builtin));
+    }
+
+    /// Sets which side effects builtins may have.
+    pub fn set_capabilities(&mut self, capabilities: Capabilities) {
+        *self.capabilities.borrow_mut() = capabilities;
+    }
+
+    pub fn get_global(&self, name: &str) -> Option<Object> {
+        self.env.borrow().get(name)
+    }
+
+    pub fn eval_program(&mut self, program: &Program) -> Result<Object> {
+        self.reset_usage();
+        let env = self.env.clone();
+        self.eval_program_in(program, &env)
+    }
+
+    fn eval_program_in(&mut self, program: &Program, env: &Env) -> Result<Object> {
+        let mut result = Object::Null(Null);
+
+        for statement in &program.statements {
+            match self.eval_statement(statement, env) {
+                Ok(value) => result = value,
+                unwind => return returned(unwind),
+            }
+        }
+
+        Ok(result)
+    }
+
+    fn eval_block_statement(&mut self, block: &BlockStatement, env: &Env) -> Flow<Object> {
+        stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT, || {
+            self.eval_block_statement_body(block, env)
+        })
+    }
+
+    fn eval_block_statement_body(&mut self, block: &BlockStatement, env: &Env) -> Flow<Object> {
+        let mut result = Object::Null(Null);
+
+        for statement in &block.statements {
+            result = self.eval_statement(statement, env)?;
+        }
+
+        Ok(result)
+    }
+
+    fn eval_statement(&mut self, statement: &Statement, env: &Env) -> Flow<Object> {
+        self.tick()?;
+
+        match statement {
+            Statement::Let {
+                pattern,
+                mutable,
+                value,
+                ..
+            } => {
+                let value = self.eval_expression(value, env)?;
+                self.destructure(pattern, value, *mutable, env)?;
+                Ok(Object::Null(Null))
+            }
+            Statement::Return { value, .. } => {
+                let value = self.eval_expression(value, env)?;
+                Err(Unwind::Return(value))
+            }
+            Statement::Function {
+                name,
+                parameters,
+                body,
+                ..
+            } => {
+                let function = Object::Function(Rc::new(Function {
+                    name: Some(name.value.clone()),
+                    parameters: parameters.clone(),
+                    body: body.clone(),
+                    env: env.clone(),
+                    file: self.file.clone(),
+                }));
+                env.borrow_mut().set(name.value.clone(), function);
+                Ok(Object::Null(Null))
+            }
+            Statement::While {
+                condition, body, ..
+            } => {
+                while self.eval_expression(condition, env)?.is_truthy() {
+                    self.tick()?;
+                    match self.eval_block_statement(body, env) {
+                        Ok(_) | Err(Unwind::Continue) => {}
+                        Err(Unwind::Break) => break,
+                        Err(unwind) => return Err(unwind),
+                    }
+                }
+                Ok(Object::Null(Null))
+            }
+            Statement::For {
+                variable,
+                iterable,
+                body,
+                ..
+            } => {
+                let iterable = self.eval_expression(iterable, env)?;
+                for item in iterate(iterable)? {
+                    // Each iteration costs fuel even when the body is empty.
+                    self.tick()?;
+                    // Each iteration gets its own scope for the variable.
+                    let env = Environment::enclosed(env);
+                    env.borrow_mut().set(variable.value.clone(), item);
+                    match self.eval_block_statement(body, &env) {
+                        Ok(_) | Err(Unwind::Continue) => {}
+                        Err(Unwind::Break) => break,
+                        Err(unwind) => return Err(unwind),
+                    }
+                }
+                Ok(Object::Null(Null))
+            }
+            Statement::Break { .. } => Err(Unwind::Break),
+            Statement::Continue { .. } => Err(Unwind::Continue),
+            Statement::Throw { value, .. } => {
+                let value = self.eval_expression(value, env)?;
+                Err(Error::thrown(value).into())
+            }
+            Statement::Import { path, alias, .. } => {
+                let module = self.import(path)?;
+                env.borrow_mut()
+                    .set(alias.value.clone(), Object::Module(module));
+                Ok(Object::Null(Null))
+            }
+            Statement::Export { statement, .. } => self.eval_statement(statement, env),
+            Statement::Expression { expression, .. } => self.eval_expression(expression, env),
+        }
+    }
+
+    /// Deeply nested expressions continue on a new stack segment, like
+    /// deep recursion does.
+    fn eval_expression(&mut self, expression: &Expression, env: &Env) -> Flow<Object> {
+        stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT, || {
+            self.eval_expression_body(expression, env)
+        })
+    }
+
+    fn eval_expression_body(&mut self, expression: &Expression, env: &Env) -> Flow<Object> {
+        self.tick()?;
+
+        match expression {
+            Expression::Identifier { name, .. } => Ok(env
+                .borrow()
+                .get(name)
+                .ok_or_else(|| Error::new(format!("identifier not found: {name}")))?),
+            Expression::IntegerLiteral { value, .. } => Ok(Object::Integer(Integer(*value))),
+            Expression::FloatLiteral { value, .. } => Ok(Object::Float(Float(*value))),
+            Expression::StringLiteral { value, .. } => Ok(Object::Str(Str(value.clone()))),
+            Expression::BooleanLiteral { value, .. } => Ok(Object::Boolean(Boolean(*value))),
+            Expression::ArrayLiteral { elements, .. } => {
+                let elements = elements
+                    .iter()
+                    .map(|element| self.eval_expression(element, env))
+                    .collect::<Flow<Vec<_>>>()?;
+                let array = Object::Array(Array::from(elements));
+                self.allocate(&array)?;
+                Ok(array)
+            }
+            Expression::HashLiteral { pairs, .. } => {
+                let mut hash = BTreeMap::new();
+                for (key, value) in pairs {
+                    let key = self.eval_expression(key, env)?;
+                    let key = key.hash_key().ok_or_else(|| {
+                        Error::new(format!("unusable as hash key: {}", key.kind()))
+                    })?;
+                    let value = self.eval_expression(value, env)?;
+                    hash.insert(key, value);
+                }
+                let hash = Object::Hash(Hash(Rc::new(hash)));
+                self.allocate(&hash)?;
+                Ok(hash)
+            }
+            Expression::Prefix {
+                operator, right, ..
+            } => {
+                let right = self.eval_expression(right, env)?;
+                Ok(eval_prefix_expression(operator, right, self.overflow)?)
+            }
+            Expression::Infix {
+                left,
+                operator,
+                right,
+                ..
+            } => match operator.as_str() {
+                "&&" | "||" => self.eval_logical_expression(left, operator, right, env),
+                _ => {
+                    let left = self.eval_expression(left, env)?;
+                    let right = self.eval_expression(right, env)?;
+                    Ok(self.eval_infix_expression(operator, left, right)?)
+                }
+            },
+            Expression::If {
+                branches,
+                alternative,
+                ..
+            } => {
+                for (condition, consequence) in branches {
+                    if self.eval_expression(condition, env)?.is_truthy() {
+                        return self.eval_block_statement(consequence, env);
+                    }
+                }
+
+                match alternative {
+                    Some(alternative) => self.eval_block_statement(alternative, env),
+                    None => Ok(Object::Null(Null)),
+                }
+            }
+            Expression::Match { subject, arms, .. } => {
+                let subject = self.eval_expression(subject, env)?;
+
+                for arm in arms {
+                    let mut bindings = vec![];
+                    if self
+                        .match_pattern(&arm.pattern, &subject, &mut bindings, env)?
+                        .is_err()
+                    {
+                        continue;
+                    }
+
+                    let env = Environment::enclosed(env);
+                    for (name, value) in bindings {
+                        env.borrow_mut().set(name, value);
+                    }
+
+                    let guard = match &arm.guard {
+                        Some(guard) => self.eval_expression(guard, &env)?.is_truthy(),
+                        None => true,
+                    };
+                    if guard {
+                        return self.eval_block_statement(&arm.body, &env);
+                    }
+                }
+
+                Err(Error::new(format!("no match arm matched: {}", subject.inspect())).into())
+            }
+            Expression::Try {
+                body,
+                catch,
+                finally,
+                ..
+            } => {
+                let mut result = self.eval_block_statement(body, env);
+
+                if let Some((pattern, handler)) = catch.as_deref() {
+                    result = match result {
+                        Err(Unwind::Error(error))
+                            if !matches!(error.kind, ErrorKind::LimitExceeded(_)) =>
+                        {
+                            self.eval_catch(pattern, handler, error, env)
+                        }
+                        result => result,
+                    };
+                }
+
+                // An error, `return`, `break` or `continue` in the finally
+                // block replaces the outcome of the rest.
+                if let Some(cleanup) = finally {
+                    self.eval_block_statement(cleanup, env)?;
+                }
+
+                result
+            }
+            Expression::Conditional {
+                condition,
+                consequence,
+                alternative,
+                ..
+            } => {
+                if self.eval_expression(condition, env)?.is_truthy() {
+                    self.eval_expression(consequence, env)
+                } else {
+                    self.eval_expression(alternative, env)
+                }
+            }
+            Expression::FunctionLiteral {
+                name,
+                parameters,
+                body,
+                ..
+            } => Ok(Object::Function(Rc::new(Function {
+                name: name.clone(),
+                parameters: parameters.clone(),
+                body: body.clone(),
+                env: env.clone(),
+                file: self.file.clone(),
+            }))),
+            Expression::Call {
+                function,
+                arguments,
+                position,
+                ..
+            } => {
+                let function = self.eval_expression(function, env)?;
+                let mut values = vec![];
+                for argument in arguments {
+                    match argument {
+                        Expression::Spread {
+                            value: iterable, ..
+                        } => {
+                            let iterable = self.eval_expression(iterable, env)?;
+                            // Spreading a huge range must hit a limit before
+                            // it exhausts memory.
+                            for value in iterate(iterable)? {
+                                self.tick()?;
+                                self.allocate_elements(1)?;
+                                values.push(value);
+                            }
+                        }
+                        argument => values.push(self.eval_expression(argument, env)?),
+                    }
+                }
+                let name = match &function {
+                    Object::Function(function) => function.name.as_deref().unwrap_or("<anonymous>"),
+                    Object::Builtin(builtin) => &builtin.name,
+                    _ => return Ok(self.apply_function(function, values)?),
+                };
+                let frame = Frame {
+                    function: name.to_string(),
+                    file: self.file.as_deref().cloned(),
+                    position: *position,
+                };
+
+                self.apply_function(function, values).map_err(|mut error| {
+                    error.stack.push(frame);
+                    error.into()
+                })
+            }
+            Expression::Spread { .. } => {
+                Err(Error::new("spread is only allowed in call arguments").into())
+            }
+            Expression::Index { left, index, .. } => {
+                let left = self.eval_expression(left, env)?;
+                let index = self.eval_expression(index, env)?;
+                Ok(eval_index_expression(left, index)?)
+            }
+            Expression::Member {
+                object, property, ..
+            } => {
+                let object = self.eval_expression(object, env)?;
+                Ok(eval_member_expression(object, property)?)
+            }
+            Expression::Range {
+                start,
+                end,
+                inclusive,
+                ..
+            } => match (
+                self.eval_expression(start, env)?,
+                self.eval_expression(end, env)?,
+            ) {
+                (Object::Integer(Integer(start)), Object::Integer(Integer(end))) => {
+                    Ok(Object::Range(Range {
+                        start,
+                        end,
+                        inclusive: *inclusive,
+                    }))
+                }
+                (start, end) => Err(Error::new(format!(
+                    "range bounds must be integers, got {}..{}",
+                    start.kind(),
+                    end.kind()
+                ))
+                .into()),
+            },
+            Expression::Assign {
+                target,
+                operator,
+                value,
+                ..
+            } => {
+                let value = self.eval_expression(value, env)?;
+                self.eval_assignment(target, operator, value, env)
+            }
+        }
+    }
+
+    /// Stores `value` into `target` and returns what was stored. Compound
+    /// operators such as `+=` combine the current value with `value` first.
+    /// Collections are values, so assigning to `xs[i]` updates the
+    /// collection bound to `xs`, copying it first only if it is shared.
+    fn eval_assignment(
+        &mut self,
+        target: &Expression,
+        operator: &str,
+        value: Object,
+        env: &Env,
+    ) -> Flow<Object> {
+        let operator = operator.strip_suffix('=').unwrap_or_default();
+
+        match target {
+            Expression::Identifier { name, .. } => {
+                let value = if operator.is_empty() {
+                    value
+                } else {
+                    let current = env
+                        .borrow()
+                        .get(name)
+                        .ok_or_else(|| assign_error(name, AssignError::Undeclared))?;
+                    self.eval_infix_expression(operator, current, value)?
+                };
+
+                let result = env.borrow_mut().assign(name, value.clone());
+                result.map_err(|error| assign_error(name, error))?;
+                Ok(value)
+            }
+            Expression::Index { .. } => {
+                // `xs[i][j]` is the root `xs` and the indices `i` and `j`,
+                // each evaluated exactly once, in order.
+                let mut root = target;
+                let mut index_expressions = vec![];
+                while let Expression::Index { left, index, .. } = root {
+                    index_expressions.push(index.as_ref());
+                    root = left;
+                }
+                index_expressions.reverse();
+                let Expression::Identifier { name, .. } = root else {
+                    return Err(Error::new(format!("invalid assignment target: {root}")).into());
+                };
+
+                let mut indices = vec![];
+                for index in index_expressions {
+                    indices.push(self.eval_expression(index, env)?);
+                }
+
+                // While the container is out of its binding it is the only
+                // reference to its elements, so writes to it happen in place.
+                let taken = env.borrow_mut().take(name);
+                let mut container = taken.map_err(|error| assign_error(name, error))?;
+                let result = self.assign_index(&mut container, &indices, operator, value);
+                env.borrow_mut()
+                    .assign(name, container)
+                    .expect("the binding was just taken");
+                Ok(result?)
+            }
+            other => Err(Error::new(format!("invalid assignment target: {other}")).into()),
+        }
+    }
+
+    /// Stores `value` at the end of the path `indices` into `container`,
+    /// combining it with the current element first for compound operators.
+    /// Returns the value stored.
+    fn assign_index(
+        &mut self,
+        container: &mut Object,
+        indices: &[Object],
+        operator: &str,
+        value: Object,
+    ) -> Result<Object> {
+        let (index, rest) = indices
+            .split_first()
+            .expect("index targets have at least one index");
+
+        if !rest.is_empty() {
+            let element = index_slot(container, index, false)?;
+            return self.assign_index(element, rest, operator, value);
+        }
+
+        let value = if operator.is_empty() {
+            value
+        } else {
+            let current = eval_index_expression(container.clone(), index.clone())?;
+            self.eval_infix_expression(operator, current, value)?
+        };
+        self.allocate(&value)?;
+        *index_slot(container, index, true)? = value.clone();
+        Ok(value)
+    }
+
+    /// Binds the names in `pattern` to the matching parts of `value`, failing
+    /// when the value does not have the pattern's shape.
+    fn destructure(
+        &mut self,
+        pattern: &Pattern,
+        value: Object,
+        mutable: bool,
+        env: &Env,
+    ) -> Flow<()> {
+        if let Pattern::Binding(name) = pattern {
+            bind(env, name.value.clone(), value, mutable);
+            return Ok(());
+        }
+
+        let mut bindings = vec![];
+        if let Err(reason) = self.match_pattern(pattern, &value, &mut bindings, env)? {
+            let message = format!("cannot destructure {}: {reason}", value.inspect());
+            return Err(Error::new(message).into());
+        }
+
+        for (name, value) in bindings {
+            bind(env, name, value, mutable);
+        }
+        Ok(())
+    }
+
+    /// Runs a `catch` handler for `error`. If the caught value does not fit
+    /// `pattern`, the handler does not apply and `error` is raised again.
+    fn eval_catch(
+        &mut self,
+        pattern: &Pattern,
+        handler: &BlockStatement,
+        error: Error,
+        env: &Env,
+    ) -> Flow<Object> {
+        let value = error.to_object();
+        let mut bindings = vec![];
+        if self
+            .match_pattern(pattern, &value, &mut bindings, env)?
+            .is_err()
+        {
+            return Err(error.into());
+        }
+
+        let env = Environment::enclosed(env);
+        for (name, value) in bindings {
+            bind(&env, name, value, false);
+        }
+        self.eval_block_statement(handler, &env)
+    }
+
+    /// Tests `value` against `pattern`, collecting the names it binds. A
+    /// value that does not fit gives `Ok(Err(reason))`, so callers can tell
+    /// a mismatch apart from an error while evaluating the pattern.
+    fn match_pattern(
+        &mut self,
+        pattern: &Pattern,
+        value: &Object,
+        bindings: &mut Vec<(String, Object)>,
+        env: &Env,
+    ) -> Flow<std::result::Result<(), String>> {
+        match (pattern, value) {
+            (Pattern::Wildcard, _) => Ok(Ok(())),
+            (Pattern::Binding(name), value) => {
+                bindings.push((name.value.clone(), value.clone()));
+                Ok(Ok(()))
+            }
+            (Pattern::Literal(expression), value) => {
+                let literal = self.eval_expression(expression, env)?;
+                let equal = eval_infix_expression("==", literal, value.clone(), self.overflow)?;
+                if equal.is_truthy() {
+                    Ok(Ok(()))
+                } else {
+                    Ok(Err(format!("expected {pattern}, got {}", value.inspect())))
+                }
+            }
+            (Pattern::Array { elements, rest }, Object::Array(Array(values))) => {
+                let (length_matches, at_least) = match rest {
+                    Some(_) => (values.len() >= elements.len(), "at least "),
+                    None => (values.len() == elements.len(), ""),
+                };
+                if !length_matches {
+                    let noun = if elements.len() == 1 {
+                        "element"
+                    } else {
+                        "elements"
+                    };
+                    return Ok(Err(format!(
+                        "expected {at_least}{} {noun}, got {}",
+                        elements.len(),
+                        values.len()
+                    )));
+                }
+
+                for (element, value) in elements.iter().zip(values.iter()) {
+                    if let Err(reason) = self.match_pattern(element, value, bindings, env)? {
+                        return Ok(Err(reason));
+                    }
+                }
+
+                match rest {
+                    Some(rest) => {
+                        let rest_values =
+                            Object::Array(Array::from(values[elements.len()..].to_vec()));
+                        self.match_pattern(rest, &rest_values, bindings, env)
+                    }
+                    None => Ok(Ok(())),
+                }
+            }
+            (Pattern::Hash(pairs), Object::Hash(Hash(values))) => {
+                for (key, pattern) in pairs {
+                    let key = self.eval_expression(key, env)?;
+                    let key = key.hash_key().ok_or_else(|| {
+                        Error::new(format!("unusable as hash key: {}", key.kind()))
+                    })?;
+                    let Some(value) = values.get(&key) else {
+                        return Ok(Err(format!("missing key {key}")));
+                    };
+                    if let Err(reason) = self.match_pattern(pattern, value, bindings, env)? {
+                        return Ok(Err(reason));
+                    }
+                }
+                Ok(Ok(()))
+            }
+            (Pattern::Array { .. }, value) => {
+                Ok(Err(format!("expected an array, got {}", value.kind())))
+            }
+            (Pattern::Hash(_), value) => Ok(Err(format!("expected a hash, got {}", value.kind()))),
+        }
+    }
+
+    /// `&&` and `||` only evaluate their right operand when the left one
+    /// does not already decide the result, and always produce a boolean.
+    fn eval_logical_expression(
+        &mut self,
+        left: &Expression,
+        operator: &str,
+        right: &Expression,
+        env: &Env,
+    ) -> Flow<Object> {
+        let left = self.eval_expression(left, env)?.is_truthy();
+
+        let value = match (operator, left) {
+            ("&&", false) => false,
+            ("||", true) => true,
+            _ => self.eval_expression(right, env)?.is_truthy(),
+        };
+
+        Ok(Object::Boolean(Boolean(value)))
+    }
+
+    /// Strings and big integers built by an operator count towards the
+    /// allocation limit.
+    fn eval_infix_expression(
+        &mut self,
+        operator: &str,
+        left: Object,
+        right: Object,
+    ) -> Result<Object> {
+        let value = eval_infix_expression(operator, left, right, self.overflow)?;
+        if let Object::Str(_) | Object::BigInteger(_) = value {
+            self.allocate(&value)?;
+        }
+        Ok(value)
+    }
+
+    fn apply_function(&mut self, function: Object, arguments: Vec<Object>) -> Result<Object> {
+        self.enter_call()?;
+        // Deep recursion continues on a new stack segment instead of
+        // overflowing the thread's stack.
+        let result = stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT, || {
+            self.call_function(function, arguments)
+        });
+        self.exit_call();
+        result
+    }
+
+    fn call_function(&mut self, function: Object, arguments: Vec<Object>) -> Result<Object> {
+        let function = match function {
+            Object::Function(function) => function,
+            Object::Builtin(builtin) => return builtin.call(&arguments),
+            other => return Err(Error::new(format!("not a function: {}", other.kind()))),
+        };
+
+        let parameters = &function.parameters;
+        let required = parameters
+            .iter()
+            .filter(|parameter| parameter.default.is_none() && !parameter.rest)
+            .count();
+        let positional = parameters
+            .iter()
+            .filter(|parameter| !parameter.rest)
+            .count();
+        let variadic = positional < parameters.len();
+
+        if arguments.len() < required || (!variadic && arguments.len() > positional) {
+            let expected = if variadic {
+                format!("at least {required}")
+            } else if required < positional {
+                format!("{required} to {positional}")
+            } else {
+                required.to_string()
+            };
+
+            return Err(Error::new(format!(
+                "wrong number of arguments to {}: expected {expected}, got {}",
+                function.display_name(),
+                arguments.len()
+            )));
+        }
+
+        // Defaults are evaluated in the call's environment, so they can
+        // refer to the parameters before them.
+        let env = Environment::enclosed(&function.env);
+        let caller_file = std::mem::replace(&mut self.file, function.file.clone());
+        let result = self
+            .bind_arguments(parameters, arguments, &env)
+            .and_then(|()| self.eval_block_statement(&function.body, &env));
+        self.file = caller_file;
+
+        returned(result)
+    }
+
+    fn bind_arguments(
+        &mut self,
+        parameters: &[Parameter],
+        arguments: Vec<Object>,
+        env: &Env,
+    ) -> Flow<()> {
+        let mut arguments = arguments.into_iter();
+        for parameter in parameters {
+            let value = if parameter.rest {
+                let rest = Object::Array(arguments.by_ref().collect());
+                self.allocate(&rest)?;
+                rest
+            } else if let Some(argument) = arguments.next() {
+                argument
+            } else if let Some(default) = &parameter.default {
+                self.eval_expression(default, env)?
+            } else {
+                unreachable!("arity was checked by the caller")
+            };
+            self.destructure(&parameter.pattern, value, false, env)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Evaluator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Ends unwinding at a function body or the top level of a program, where
+/// `return` gives the result.
+fn returned(flow: Flow<Object>) -> Result<Object> {
+    match flow {
+        Ok(value) | Err(Unwind::Return(value)) => Ok(value),
+        Err(Unwind::Error(error)) => Err(error),
+        Err(Unwind::Break) => Err(Error::new("break outside of a loop")),
+        Err(Unwind::Continue) => Err(Error::new("continue outside of a loop")),
+    }
+}
+
+fn bind(env: &Env, name: String, value: Object, mutable: bool) {
+    if mutable {
+        env.borrow_mut().set_mutable(name, value);
+    } else {
+        env.borrow_mut().set(name, value);
+    }
+}
+
+fn eval_index_expression(left: Object, index: Object) -> Result<Object> {
+    match (&left, index) {
+        (Object::Array(Array(elements)), Object::Integer(Integer(index))) => usize::try_from(index)
+            .ok()
+            .and_then(|i| elements.get(i).cloned())
+            .ok_or_else(|| {
+                Error::new(format!(
+                    "index out of range: {index} (length {})",
+                    elements.len()
+                ))
+            }),
+        (Object::Str(Str(string)), Object::Integer(Integer(index))) => usize::try_from(index)
+            .ok()
+            .and_then(|i| string.chars().nth(i))
+            .map(|c| Object::Str(Str(c.to_string())))
+            .ok_or_else(|| {
+                Error::new(format!(
+                    "index out of range: {index} (length {})",
+                    string.chars().count()
+                ))
+            }),
+        (Object::Hash(Hash(pairs)), key) => {
+            let key = key
+                .hash_key()
+                .ok_or_else(|| Error::new(format!("unusable as hash key: {}", key.kind())))?;
+            pairs
+                .get(&key)
+                .cloned()
+                .ok_or_else(|| Error::new(format!("key not found: {key}")))
+        }
+        (left, index) => Err(Error::new(format!(
+            "index operator not supported: {}[{}]",
+            left.kind(),
+            index.kind()
+        ))),
+    }
+}
+
+fn eval_member_expression(object: Object, property: &str) -> Result<Object> {
+    match &object {
+        Object::Module(Module { path, exports }) => {
+            exports.get(property).cloned().ok_or_else(|| {
+                Error::new(format!(
+                    "module {} has no export {property}",
+                    module::display_path(path)
+                ))
+            })
+        }
+        Object::Hash(Hash(pairs)) => pairs
+            .get(&HashKey::Str(property.to_string()))
+            .cloned()
+            .ok_or_else(|| Error::new(format!("key not found: {property:?}"))),
+        object => Err(Error::new(format!(
+            "member access not supported: {}.{property}",
+            object.kind()
+        ))),
+    }
+}
+
+/// The element of `container` at `index`, copying the collection first if
+/// it is shared. With `insert`, a missing hash key is added as null.
+fn index_slot<'a>(
+    container: &'a mut Object,
+    index: &Object,
+    insert: bool,
+) -> Result<&'a mut Object> {
+    match (container, index) {
+        (Object::Array(Array(elements)), Object::Integer(Integer(index))) => {
+            let length = elements.len();
+            usize::try_from(*index)
+                .ok()
+                .filter(|&i| i < length)
+                .map(|i| &mut Rc::make_mut(elements)[i])
+                .ok_or_else(|| Error::new(format!("index out of range: {index} (length {length})")))
+        }
+        (Object::Hash(Hash(pairs)), key) => {
+            let key = key
+                .hash_key()
+                .ok_or_else(|| Error::new(format!("unusable as hash key: {}", key.kind())))?;
+            let pairs = Rc::make_mut(pairs);
+            if insert {
+                return Ok(pairs.entry(key).or_insert(Object::Null(Null)));
+            }
+            let missing = format!("key not found: {key}");
+            pairs.get_mut(&key).ok_or_else(|| Error::new(missing))
+        }
+        (container, index) => Err(Error::new(format!(
+            "index assignment not supported: {}[{}]",
+            container.kind(),
+            index.kind()
+        ))),
+    }
+}
+
+fn assign_error(name: &str, error: AssignError) -> Error {
+    match error {
+        AssignError::Undeclared => Error::new(format!("assignment to undeclared name: {name}")),
+        AssignError::Immutable => Error::new(format!("cannot assign to immutable binding: {name}")),
+    }
+}
+
+/// Arrays iterate over their elements, strings over their characters,
+/// hashes over their keys and ranges over their integers.
+fn iterate(iterable: Object) -> Result<Box<dyn Iterator<Item = Object>>> {
+    match iterable {
+        Object::Array(array) => Ok(Box::new(array.into_elements().into_iter())),
+        Object::Str(Str(string)) => {
+            let characters = string
+                .chars()
+                .map(|c| Object::Str(Str(c.to_string())))
+                .collect::<Vec<_>>();
+            Ok(Box::new(characters.into_iter()))
+        }
+        Object::Hash(hash) => Ok(Box::new(
+            hash.into_pairs().into_keys().map(|key| key.to_object()),
+        )),
+        Object::Range(range) => Ok(Box::new(range.iter().map(|i| Object::Integer(Integer(i))))),
+        other => Err(Error::new(format!("cannot iterate over {}", other.kind()))),
+    }
+}
+
+fn eval_prefix_expression(operator: &str, right: Object, overflow: Overflow) -> Result<Object> {
+    match (operator, right) {
+        ("!", right) => Ok(Object::Boolean(Boolean(!right.is_truthy()))),
+        ("-", Object::Integer(Integer(value))) => match (value.checked_neg(), overflow) {
+            (Some(value), _) => Ok(Object::Integer(Integer(value))),
+            (None, Overflow::Promote) => Ok(BigInteger::normalize(-BigInt::from(value))),
+            (None, Overflow::Error) => Err(Error::new(format!("integer overflow: -({value})"))),
+        },
+        ("-", Object::BigInteger(BigInteger(value))) => Ok(BigInteger::normalize(-value)),
+        ("-", Object::Float(Float(value))) => Ok(Object::Float(Float(-value))),
+        (operator, right) => Err(Error::new(format!(
+            "unknown operator: {operator}{}",
+            right.kind()
+        ))),
+    }
+}
+
+fn eval_infix_expression(
+    operator: &str,
+    left: Object,
+    right: Object,
+    overflow: Overflow,
+) -> Result<Object> {
+    match (left, right) {
+        (Object::Integer(Integer(left)), Object::Integer(Integer(right))) => {
+            eval_integer_infix_expression(operator, left, right, overflow)
+        }
+        (Object::BigInteger(BigInteger(left)), Object::BigInteger(BigInteger(right))) => {
+            eval_big_integer_infix_expression(operator, left, right)
+        }
+        (Object::BigInteger(BigInteger(left)), Object::Integer(Integer(right))) => {
+            eval_big_integer_infix_expression(operator, left, BigInt::from(right))
+        }
+        (Object::Integer(Integer(left)), Object::BigInteger(BigInteger(right))) => {
+            eval_big_integer_infix_expression(operator, BigInt::from(left), right)
+        }
+        // Mixed arithmetic promotes the integer to a float.
+        (Object::Float(Float(left)), Object::Float(Float(right))) => {
+            eval_float_infix_expression(operator, left, right)
+        }
+        (Object::Integer(Integer(left)), Object::Float(Float(right))) => {
+            eval_float_infix_expression(operator, left as f64, right)
+        }
+        (Object::Float(Float(left)), Object::Integer(Integer(right))) => {
+            eval_float_infix_expression(operator, left, right as f64)
+        }
+        (Object::BigInteger(BigInteger(left)), Object::Float(Float(right))) => {
+            eval_float_infix_expression(operator, big_to_f64(&left), right)
+        }
+        (Object::Float(Float(left)), Object::BigInteger(BigInteger(right))) => {
+            eval_float_infix_expression(operator, left, big_to_f64(&right))
+        }
+        (Object::Boolean(Boolean(left)), Object::Boolean(Boolean(right))) => match operator {
+            "==" => Ok(Object::Boolean(Boolean(left == right))),
+            "!=" => Ok(Object::Boolean(Boolean(left != right))),
+            _ => Err(Error::new(format!(
+                "unknown operator: BOOLEAN {operator} BOOLEAN"
+            ))),
+        },
+        (Object::Str(Str(left)), Object::Str(Str(right))) => match operator {
+            "+" => Ok(Object::Str(Str(left + &right))),
+            "==" => Ok(Object::Boolean(Boolean(left == right))),
+            "!=" => Ok(Object::Boolean(Boolean(left != right))),
+            _ => Err(Error::new(format!(
+                "unknown operator: STRING {operator} STRING"
+            ))),
+        },
+        (Object::Null(_), Object::Null(_)) if operator == "==" || operator == "!=" => {
+            Ok(Object::Boolean(Boolean(operator == "==")))
+        }
+        (left, right) => match operator {
+            "==" => Ok(Object::Boolean(Boolean(false))),
+            "!=" => Ok(Object::Boolean(Boolean(true))),
+            _ if left.kind() != right.kind() => Err(Error::new(format!(
+                "type mismatch: {} {operator} {}",
+                left.kind(),
+                right.kind()
+            ))),
+            _ => Err(Error::new(format!(
+                "unknown operator: {} {operator} {}",
+                left.kind(),
+                right.kind()
+            ))),
+        },
+    }
+}
+
+fn eval_integer_infix_expression(
+    operator: &str,
+    left: i64,
+    right: i64,
+    overflow: Overflow,
+) -> Result<Object> {
+    let value = match operator {
+        "+" => left.checked_add(right),
+        "-" => left.checked_sub(right),
+        "*" => left.checked_mul(right),
+        "/" | "%" if right == 0 => return Err(Error::new("division by zero")),
+        "/" => left.checked_div(right),
+        "%" => left.checked_rem(right),
+        "<" => return Ok(Object::Boolean(Boolean(left < right))),
+        ">" => return Ok(Object::Boolean(Boolean(left > right))),
+        "<=" => return Ok(Object::Boolean(Boolean(left <= right))),
+        ">=" => return Ok(Object::Boolean(Boolean(left >= right))),
+        "==" => return Ok(Object::Boolean(Boolean(left == right))),
+        "!=" => return Ok(Object::Boolean(Boolean(left != right))),
+        _ => {
+            return Err(Error::new(format!(
+                "unknown operator: INTEGER {operator} INTEGER"
+            )))
+        }
+    };
+
+    match (value, overflow) {
+        (Some(value), _) => Ok(Object::Integer(Integer(value))),
+        (None, Overflow::Promote) => {
+            eval_big_integer_infix_expression(operator, BigInt::from(left), BigInt::from(right))
+        }
+        (None, Overflow::Error) => Err(Error::new(format!(
+            "integer overflow: {left} {operator} {right}"
+        ))),
+    }
+}
+
+fn eval_big_integer_infix_expression(
+    operator: &str,
+    left: BigInt,
+    right: BigInt,
+) -> Result<Object> {
+    let value = match operator {
+        "+" => left + right,
+        "-" => left - right,
+        "*" => left * right,
+        "/" | "%" if right.is_zero() => return Err(Error::new("division by zero")),
+        "/" => left / right,
+        "%" => left % right,
+        "<" => return Ok(Object::Boolean(Boolean(left < right))),
+        ">" => return Ok(Object::Boolean(Boolean(left > right))),
+        "<=" => return Ok(Object::Boolean(Boolean(left <= right))),
+        ">=" => return Ok(Object::Boolean(Boolean(left >= right))),
+        "==" => return Ok(Object::Boolean(Boolean(left == right))),
+        "!=" => return Ok(Object::Boolean(Boolean(left != right))),
+        _ => {
+            return Err(Error::new(format!(
+                "unknown operator: INTEGER {operator} INTEGER"
+            )))
+        }
+    };
+
+    Ok(BigInteger::normalize(value))
+}
+
+fn big_to_f64(value: &BigInt) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
+
+/// Follows IEEE 754, so dividing by zero gives an infinity or NaN rather
+/// than an error.
+fn eval_float_infix_expression(operator: &str, left: f64, right: f64) -> Result<Object> {
+    let value = match operator {
+        "+" => left + right,
+        "-" => left - right,
+        "*" => left * right,
+        "/" => left / right,
+        "%" => left % right,
+        "<" => return Ok(Object::Boolean(Boolean(left < right))),
+        ">" => return Ok(Object::Boolean(Boolean(left > right))),
+        "<=" => return Ok(Object::Boolean(Boolean(left <= right))),
+        ">=" => return Ok(Object::Boolean(Boolean(left >= right))),
+        "==" => return Ok(Object::Boolean(Boolean(left == right))),
+        "!=" => return Ok(Object::Boolean(Boolean(left != right))),
+        _ => {
+            return Err(Error::new(format!(
+                "unknown operator: FLOAT {operator} FLOAT"
+            )))
+        }
+    };
+
+    Ok(Object::Float(Float(value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+    use std::time::Instant;
+
+    use crate::lexer::Lexer;
+    use crate::object::{Array, Hash, Object, ObjectType};
+    use crate::parser::Parser;
+    use crate::token::Position;
+
+    use super::{Error, ErrorKind, Evaluator, Frame, Overflow};
+
+    fn eval(input: &str) -> Result<Object, Error> {
+        eval_with(&mut Evaluator::new(), input)
+    }
+
+    /// Evaluates `input`, failing the test on any parse error. Shared with
+    /// the tests of the evaluator's submodules.
+    pub(super) fn eval_with(evaluator: &mut Evaluator, input: &str) -> Result<Object, Error> {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let program = parser.parse_program();
+        assert!(parser.errors.is_empty(), "{:?}", parser.errors);
+
+        evaluator.eval_program(&program)
+    }
+
+    fn assert_evaluates_to(tests: Vec<(&str, &str)>) {
+        for (input, expected) in tests {
+            match eval(input) {
+                Ok(value) => assert_eq!(value.inspect(), expected, "{input}"),
+                Err(error) => panic!("{input} failed with: {error}"),
+            }
+        }
+    }
+
+    #[test]
+    fn integer_expressions() {
+        // Arrange
+        let tests = vec![
+            ("5", "5"),
+            ("-10", "-10"),
+            ("-9223372036854775808", "-9223372036854775808"),
+            ("5 + 5 + 5 + 5 - 10", "10"),
+            ("2 * (5 + 10)", "30"),
+            ("50 / 2 * 2 + 10", "60"),
+            ("-7 / 2", "-3"),
+            ("17 % 5", "2"),
+            ("-17 % 5", "-2"),
+            ("0xff + 0b1 + 0o7 + 1_000", "1263"),
+        ];
+
+        // Act + Assert
+        assert_evaluates_to(tests);
+    }
+
+    #[test]
+    fn float_expressions() {
+        // Arrange
+        let tests = vec![
+            ("3.14", "3.14"),
+            ("-2.5", "-2.5"),
+            ("1e-9", "1e-9"),
+            ("0.1 + 0.2", "0.30000000000000004"),
+            ("1.5 * 2.0", "3.0"),
+            ("7.5 % 2.0", "1.5"),
+            ("1 + 0.5", "1.5"),
+            ("0.5 + 1", "1.5"),
+            ("3 / 2.0", "1.5"),
+            ("3 / 2", "1"),
+            ("1 / 0.0", "Infinity"),
+            ("-1 / 0.0", "-Infinity"),
+            ("0.0 / 0.0", "NaN"),
+            ("1 == 1.0", "true"),
+            ("2 > 1.5", "true"),
+            ("1.5 <= 1", "false"),
+            ("0.0 / 0.0 == 0.0 / 0.0", "false"),
+            ("0.0 / 0.0 != 0.0 / 0.0", "true"),
+        ];
+
+        // Act + Assert
+        assert_evaluates_to(tests);
+    }
+
+    #[test]
+    fn integer_overflow_is_an_error_by_default() {
+        // Arrange
+        let tests = vec![
+            (
+                "9223372036854775807 + 1",
+                "integer overflow: 9223372036854775807 + 1",
+            ),
+            (
+                "2 * 9223372036854775807",
+                "integer overflow: 2 * 9223372036854775807",
+            ),
+            (
+                "-9223372036854775807 - 2",
+                "integer overflow: -9223372036854775807 - 2",
+            ),
+            (
+                "let min = -9223372036854775807 - 1; -min",
+                "integer overflow: -(-9223372036854775808)",
+            ),
+        ];
+
+        for (input, expected) in tests {
+            // Act
+            let error = eval(input).unwrap_err();
+
+            // Assert
+            assert_eq!(error.message, expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn integer_overflow_promotes_when_enabled() {
+        // Arrange
+        let tests = vec![
+            ("2 * 9223372036854775807", "18446744073709551614"),
+            ("9223372036854775807 + 1", "9223372036854775808"),
+            (
+                "let min = -9223372036854775807 - 1; -min",
+                "9223372036854775808",
+            ),
+            (
+                "let min = -9223372036854775807 - 1; min / -1",
+                "9223372036854775808",
+            ),
+            ("let min = -9223372036854775807 - 1; min % -1", "0"),
+            (
+                "let big = 9223372036854775807 * 9223372036854775807; big * big",
+                "7237005577332262210834635695349653859421902880380109739573089701262786560001",
+            ),
+            ("(9223372036854775807 + 1) - 1", "9223372036854775807"),
+            ("(9223372036854775807 + 1) / 2", "4611686018427387904"),
+            ("(9223372036854775807 + 1) % 10", "8"),
+            ("-(9223372036854775807 + 1)", "-9223372036854775808"),
+            ("9223372036854775807 + 1 > 9223372036854775807", "true"),
+            ("9223372036854775807 + 1 == 9223372036854775807", "false"),
+            ("(9223372036854775807 + 1) * 0.5", "4.611686018427388e18"),
+        ];
+
+        for (input, expected) in tests {
+            // Act
+            let mut evaluator = Evaluator::new();
+            evaluator.set_overflow(Overflow::Promote);
+            let value = eval_with(&mut evaluator, input).unwrap();
+
+            // Assert
+            assert_eq!(value.inspect(), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn boolean_expressions() {
+        // Arrange
+        let tests = vec![
+            ("true", "true"),
+            ("!5", "false"),
+            ("!!true", "true"),
+            ("1 < 2", "true"),
+            ("1 <= 1", "true"),
+            ("2 <= 1", "false"),
+            ("1 >= 1", "true"),
+            ("1 >= 2", "false"),
+            ("1 == 1", "true"),
+            ("1 != 1", "false"),
+            ("(1 < 2) == true", "true"),
+            ("true != false", "true"),
+            ("\"a\" == \"a\"", "true"),
+            ("1 == true", "false"),
+        ];
+
+        // Act + Assert
+        assert_evaluates_to(tests);
+    }
+
+    #[test]
+    fn logical_operators() {
+        // Arrange
+        let tests = vec![
+            ("true && true", "true"),
+            ("true && false", "false"),
+            ("false || true", "true"),
+            ("false || false", "false"),
+            ("1 < 2 && 2 < 3", "true"),
+            ("true || false && false", "true"),
+            ("5 && \"yes\"", "true"),
+        ];
+
+        // Act + Assert
+        assert_evaluates_to(tests);
+    }
+
+    #[test]
+    fn logical_operators_short_circuit() {
+        // Arrange
+        let tests = vec![("false && crash()", "false"), ("true || crash()", "true")];
+
+        // Act + Assert
+        assert_evaluates_to(tests);
+        assert_eq!(
+            eval("true && crash()").unwrap_err(),
+            Error::new("identifier not found: crash")
+        );
+    }
+
+    #[test]
+    fn if_else_expressions() {
+        // Arrange
+        let tests = vec![
+            ("if (true) { 10 }", "10"),
+            ("if (false) { 10 }", "null"),
+            ("if (1 < 2) { 10 } else { 20 }", "10"),
+            ("if (1 > 2) { 10 } else { 20 }", "20"),
+            (
+                "let x = 2; if (x == 1) { 10 } else if (x == 2) { 20 } else { 30 }",
+                "20",
+            ),
+            (
+                "let x = 3; if (x == 1) { 10 } else if (x == 2) { 20 } else { 30 }",
+                "30",
+            ),
+            (
+                "let x = 3; if (x == 1) { 10 } else if (x == 2) { 20 }",
+                "null",
+            ),
+        ];
+
+        // Act + Assert
+        assert_evaluates_to(tests);
+    }
+
+    #[test]
+    fn match_expressions() {
+        // Arrange
+        let tests = vec![
+            ("match (0) { 0 => \"zero\", _ => \"other\" }", "zero"),
+            ("match (7) { 0 => \"zero\", _ => \"other\" }", "other"),
+            ("match (-1) { -1 => \"minus one\", _ => 0 }", "minus one"),
+            ("match (2.0) { 2 => \"two\", _ => 0 }", "two"),
+            ("match (\"b\") { \"a\" => 1, \"b\" => 2, _ => 3 }", "2"),
+            ("match (true) { false => 0, true => 1 }", "1"),
+            ("match (5) { n => n * 2 }", "10"),
+            ("match (5) { n if n > 3 => \"big\", n => \"small\" }", "big"),
+            (
+                "match (2) { n if n > 3 => \"big\", n => \"small\" }",
+                "small",
+            ),
+            ("match ([1, 2, 3]) { [x, ...rest] => rest }", "[2, 3]"),
+            (
+                "match ([1, 2]) { [a, b, c] => 3, [a, b] => a + b, _ => 0 }",
+                "3",
+            ),
+            ("match ([]) { [x, ..._] => x, [] => \"empty\" }", "empty"),
+            (
+                "match ([[1, 2], 3]) { [[a, b], c] => a + b + c, _ => 0 }",
+                "6",
+            ),
+            (
+                "match (5) { [x] => x, _ => \"not an array\" }",
+                "not an array",
+            ),
+            (
+                "match ({\"k\": 1, \"j\": 2}) { {\"k\": v} => v, _ => 0 }",
+                "1",
+            ),
+            ("match ({\"j\": 2}) { {\"k\": v} => v, _ => 0 }", "0"),
+            (
+                "match ({\"point\": [1, 2]}) { {\"point\": [x, y]} => x + y, _ => 0 }",
+                "3",
+            ),
+            ("match (1) { 1 => { let y = 2; y * 3 }, _ => 0 }", "6"),
+            ("let x = 1; match (2) { x => x }; x", "1"),
+            (
+                "fn f(n) { match (n) { 0 => { return \"early\"; }, _ => 1 }; \"late\" } f(0)",
+                "early",
+            ),
+        ];
+
+        // Act + Assert
+        assert_evaluates_to(tests);
+    }
+
+    #[test]
+    fn destructuring() {
+        // Arrange
+        let tests = vec![
+            ("let [a, b] = [1, 2]; a + b", "3"),
+            ("let [a, b, ...rest] = [1, 2, 3, 4]; rest", "[3, 4]"),
+            ("let [first, ..._] = [1, 2, 3]; first", "1"),
+            ("let [_, [x, y]] = [0, [1, 2]]; x * y", "2"),
+            (
+                "let person = {\"name\": \"Ada\", \"age\": 36}; let {name, age} = person; name",
+                "Ada",
+            ),
+            ("let {\"k\": [v, w]} = {\"k\": [1, 2]}; v + w", "3"),
+            ("let mut [a, b] = [1, 2]; a = b; a", "2"),
+            ("fn add([a, b]) { a + b } add([3, 4])", "7"),
+            (
+                "fn name({name}) { name } name({\"name\": \"Bo\", \"x\": 1})",
+                "Bo",
+            ),
+            ("fn f([a, b] = [1, 2], ...rest) { a + b } f()", "3"),
+            ("let swap = fn([a, b]) { [b, a] }; swap([1, 2])", "[2, 1]"),
+        ];
+
+        // Act + Assert
+        assert_evaluates_to(tests);
+    }
+
+    #[test]
+    fn conditional_expressions() {
+        // Arrange
+        let tests = vec![
+            ("true ? 1 : 2", "1"),
+            ("if (false) { 1 } ? 1 : 2", "2"),
+            (
+                "let n = 0; n > 0 ? \"positive\" : n < 0 ? \"negative\" : \"zero\"",
+                "zero",
+            ),
+            ("let mut x = 0; true ? 1 : (x = 5); x", "0"),
+            (
+                "fn fib(n) { n < 2 ? n : fib(n - 1) + fib(n - 2) } fib(10)",
+                "55",
+            ),
+        ];
+
+        // Act + Assert
+        assert_evaluates_to(tests);
+    }
+
+    #[test]
+    fn return_statements() {
+        // Arrange
+        let tests = vec![
+            ("return 10; 9;", "10"),
+            ("9; return 2 * 5; 9;", "10"),
+            ("if (10 > 1) { if (10 > 1) { return 10; } return 1; }", "10"),
+            (
+                "let f = fn() { let y = if (true) { return 5; } else { 1 }; 99 }; f()",
+                "5",
+            ),
+            (
+                "let f = fn() { [1, if (true) { return 2; } else { 3 }] }; f()",
+                "2",
+            ),
+            (
+                "let f = fn(x) { x }; let g = fn() { f(match (1) { _ => { return 3; } }) + 1 }; g()",
+                "3",
+            ),
+            ("let f = fn() { 1 + if (true) { return 4; } }; f()", "4"),
+        ];
+
+        // Act + Assert
+        assert_evaluates_to(tests);
+    }
+
+    #[test]
+    fn let_statements_and_functions() {
+        // Arrange
+        let tests = vec![
+            ("let a = 5; a * 2", "10"),
+            ("let add = fn(x, y) { x + y }; add(5, add(5, 5))", "15"),
+            ("let identity = fn(x) { return x; 1 }; identity(5)", "5"),
+            (
+                "let adder = fn(x) { fn(y) { x + y } }; let add_two = adder(2); add_two(3)",
+                "5",
+            ),
+            ("\"Hello\" + \" \" + \"World!\"", "Hello World!"),
+            ("fn(x) { x }", "fn(x) { x }"),
+        ];
+
+        // Act + Assert
+        assert_evaluates_to(tests);
+    }
+
+    #[test]
+    fn named_functions() {
+        // Arrange
+        let tests = vec![
+            (
+                "fn fib(n) { if (n < 2) { n } else { fib(n - 1) + fib(n - 2) } } fib(15)",
+                "610",
+            ),
+            (
+                "fn is_even(n) { if (n == 0) { true } else { is_odd(n - 1) } } fn is_odd(n) { if (n == 0) { false } else { is_even(n - 1) } } is_even(10)",
+                "true",
+            ),
+            ("fn double(x) { x * 2 } double", "fn double(x) { (x * 2) }"),
+            ("let inc = fn(x) { x + 1 }; inc", "fn inc(x) { (x + 1) }"),
+            ("fn f() {} let g = f; g", "fn f() {  }"),
+            ("fn f() { 1 }", "null"),
+        ];
+
+        // Act + Assert
+        assert_evaluates_to(tests);
+    }
+
+    #[test]
+    fn default_and_rest_parameters() {
+        // Arrange
+        let tests = vec![
+            ("fn f(x, y = 10) { x + y } f(1)", "11"),
+            ("fn f(x, y = 10) { x + y } f(1, 2)", "3"),
+            ("fn f(x, y = x * 2) { y } f(4)", "8"),
+            ("let n = 0; fn f(x = n) { x } f()", "0"),
+            ("fn f(first, ...rest) { rest } f(1, 2, 3)", "[2, 3]"),
+            ("fn f(first, ...rest) { rest } f(1)", "[]"),
+            ("fn f(x = 1, ...rest) { [x, rest] } f()", "[1, []]"),
+            ("fn f(...all) { all } f(1, 2)", "[1, 2]"),
+            (
+                "fn add(x, y, z) { x + y + z } let xs = [1, 2, 3]; add(...xs)",
+                "6",
+            ),
+            ("fn add(x, y, z) { x + y + z } add(1, ...[2], ...[3])", "6"),
+            (
+                "fn f(...all) { all } f(...0..3, ...\"ab\")",
+                "[0, 1, 2, a, b]",
+            ),
+        ];
+
+        // Act + Assert
+        assert_evaluates_to(tests);
+    }
+
+    #[test]
+    fn while_loops() {
+        // Arrange
+        let tests = vec![
+            ("let i = 0; while (i < 5) { let i = i + 1; } i", "5"),
+            ("while (false) { 1 }", "null"),
+            (
+                "let i = 0; while (true) { let i = i + 1; if (i == 3) { break; } } i",
+                "3",
+            ),
+            (
+                "let i = 0; let odd = 0; while (i < 10) { let i = i + 1; if (i % 2 == 0) { continue; } let odd = odd + 1; } odd",
+                "5",
+            ),
+            (
+                "let f = fn() { while (true) { return 42; } }; f()",
+                "42",
+            ),
+            (
+                "let mut i = 0; while (true) { i += 1; let y = if (i > 3) { break; } else { 1 }; } i",
+                "4",
+            ),
+            (
+                "let mut n = 0; for (i in 0..5) { n += [if (i % 2 == 0) { continue; } else { i }][0]; } n",
+                "4",
+            ),
+        ];
+
+        // Act + Assert
+        assert_evaluates_to(tests);
+    }
+
+    #[test]
+    fn arrays_and_hashes() {
+        // Arrange
+        let tests = vec![
+            ("[1, 2 * 2, 3 + 3]", "[1, 4, 6]"),
+            ("[1, 2, 3][0]", "1"),
+            ("let i = 2; [1, 2, 3][i]", "3"),
+            ("let xs = [1, [2, 3]]; xs[1][0]", "2"),
+            ("\"héllo\"[1]", "é"),
+            ("{\"b\": 2, \"a\": 1}", "{a: 1, b: 2}"),
+            ("{\"a\": 1}[\"a\"]", "1"),
+            ("{1: \"one\", true: \"yes\"}[true]", "yes"),
+            ("let key = \"k\"; {key: 5}[\"k\"]", "5"),
+            ("1..3", "1..3"),
+            ("1..=3", "1..=3"),
+            ("let point = {\"x\": 1, \"y\": 2}; point.x + point.y", "3"),
+            ("{\"a\": {\"b\": [7]}}.a.b[0]", "7"),
+        ];
+
+        // Act + Assert
+        assert_evaluates_to(tests);
+    }
+
+    #[test]
+    fn for_loops() {
+        // Arrange
+        let tests = vec![
+            ("let mut sum = 0; for (x in [1, 2, 3]) { sum += x; } sum", "6"),
+            ("let mut sum = 0; for (i in 0..10) { sum += i; } sum", "45"),
+            ("let mut sum = 0; for (i in 0..=10) { sum += i; } sum", "55"),
+            ("let mut n = 0; for (i in 5..1) { n += 1; } n", "0"),
+            ("let mut s = \"\"; for (c in \"abc\") { s = c + s; } s", "cba"),
+            (
+                "let mut ks = \"\"; for (k in {\"b\": 1, \"a\": 2}) { ks += k; } ks",
+                "ab",
+            ),
+            (
+                "let mut last = 0; for (i in 0..1000000000) { last = i; if (i == 3) { break; } } last",
+                "3",
+            ),
+            (
+                "let mut odd = 0; for (i in 0..10) { if (i % 2 == 0) { continue; } odd += 1; } odd",
+                "5",
+            ),
+            ("let x = 10; for (x in [1, 2]) {} x", "10"),
+            ("let n = 0; for (i in 0..3) { let n = n + 1; } n", "0"),
+            (
+                "let mut fs = {}; for (i in 0..3) { fs[i] = fn() { i }; } [fs[0](), fs[2]()]",
+                "[0, 2]",
+            ),
+            (
+                "let find = fn(xs) { for (x in xs) { if (x > 1) { return x; } } -1 }; find([1, 5, 7])",
+                "5",
+            ),
+        ];
+
+        // Act + Assert
+        assert_evaluates_to(tests);
+    }
+
+    #[test]
+    fn assignment() {
+        // Arrange
+        let tests = vec![
+            ("let mut x = 1; x = x + 1; x", "2"),
+            ("let mut x = 1; x = 5", "5"),
+            ("let mut x = 10; x += 5; x -= 3; x *= 2; x /= 4; x", "6"),
+            ("let mut s = \"a\"; s += \"b\"; s", "ab"),
+            ("let mut x = 0; let mut y = 0; x = y = 7; x + y", "14"),
+            ("let mut i = 0; while (i < 5) { i += 1; } i", "5"),
+            (
+                "let mut n = 0; let inc = fn() { n += 1 }; inc(); inc(); n",
+                "2",
+            ),
+            ("let mut xs = [1, 2, 3]; xs[1] = 20; xs", "[1, 20, 3]"),
+            ("let mut xs = [1, 2, 3]; xs[2] *= 10; xs", "[1, 2, 30]"),
+            (
+                "let mut m = [[1, 2], [3, 4]]; m[1][0] = 9; m",
+                "[[1, 2], [9, 4]]",
+            ),
+            ("let mut h = {}; h[\"k\"] = 1; h[\"k\"] += 1; h", "{k: 2}"),
+            (
+                "let mut h = {\"xs\": [1]}; h[\"xs\"][0] = 5; h",
+                "{xs: [5]}",
+            ),
+            ("let a = [1]; let mut b = a; b[0] = 2; a", "[1]"),
+            (
+                "let mut n = 0; fn next() { n += 1; n - 1 } let mut xs = [1, 2]; xs[next()] += 10; [xs, n]",
+                "[[11, 2], 1]",
+            ),
+            (
+                "let mut n = 0; fn next() { n += 1; n - 1 } let mut m = [[0, 0], [0, 0]]; m[next()][next()] += 5; [m, n]",
+                "[[[0, 5], [0, 0]], 2]",
+            ),
+        ];
+
+        // Act + Assert
+        assert_evaluates_to(tests);
+    }
+
+    #[test]
+    fn index_assignment_writes_unshared_collections_in_place() {
+        // Arrange
+        let mut evaluator = Evaluator::new();
+        eval_with(&mut evaluator, "let mut xs = [1, 2, 3]; let mut h = {};").unwrap();
+        let address = |evaluator: &Evaluator, name: &str| match &evaluator.env.borrow().get(name) {
+            Some(Object::Array(Array(elements))) => Rc::as_ptr(elements) as usize,
+            Some(Object::Hash(Hash(pairs))) => Rc::as_ptr(pairs) as usize,
+            other => panic!("{name} is {other:?}"),
+        };
+        let before = [address(&evaluator, "xs"), address(&evaluator, "h")];
+
+        // Act
+        eval_with(&mut evaluator, "xs[0] = 5; xs[1] += 1; h[1] = 2;").unwrap();
+
+        // Assert
+        let after = [address(&evaluator, "xs"), address(&evaluator, "h")];
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn index_assignment_fill_loop_is_linear() {
+        // Arrange
+        let fill = |n: i64| {
+            let input = format!("let mut h = {{}}; for (i in 0..{n}) {{ h[i] = i; }}");
+            (0..3)
+                .map(|_| {
+                    let start = Instant::now();
+                    eval(&input).unwrap();
+                    start.elapsed()
+                })
+                .min()
+                .unwrap()
+        };
+
+        // Act
+        let small = fill(5_000);
+        let large = fill(20_000);
+
+        // Assert
+        // Four times the work takes about four times as long; copying the
+        // hash on every write would take sixteen.
+        assert!(large < small * 8, "{small:?} for 5000, {large:?} for 20000");
+    }
+
+    #[test]
+    fn lookups_share_collections_and_functions() {
+        // Arrange
+        let input =
+            "let xs = [1, [2]]; let h = {\"a\": 1}; let f = fn() { xs }; [xs, f(), h, h, f, f]";
+
+        // Act
+        let value = eval(input).unwrap();
+
+        // Assert
+        let Object::Array(Array(values)) = &value else {
+            panic!("expected an array, got {value:?}");
+        };
+        match values.as_slice() {
+            [Object::Array(Array(a)), Object::Array(Array(b)), Object::Hash(Hash(c)), Object::Hash(Hash(d)), Object::Function(e), Object::Function(f)] =>
+            {
+                assert!(Rc::ptr_eq(a, b));
+                assert!(Rc::ptr_eq(c, d));
+                assert!(Rc::ptr_eq(e, f));
+            }
+            other => panic!("unexpected values: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn deep_nesting_does_not_overflow_the_stack() {
+        // Arrange
+        let tests = vec![
+            (
+                "[".repeat(999) + &"]".repeat(999),
+                "[".repeat(999) + &"]".repeat(999),
+            ),
+            ("-".repeat(998) + "1", "1".to_string()),
+            (
+                "if (true) { ".repeat(499) + "2" + &"}".repeat(499),
+                "2".to_string(),
+            ),
+            (
+                "let mut x = []; for (i in 0..3000) { x = [x]; } x".to_string(),
+                "[".repeat(3001) + &"]".repeat(3001),
+            ),
+            (
+                "let mut x = 0; for (i in 0..10000) { x = {\"x\": [x]}; } x".to_string(),
+                "{x: [".repeat(10_000) + "0" + &"]}".repeat(10_000),
+            ),
+            (
+                "let mut x = 0; for (i in 0..100000) { x = {\"x\": [x]}; } 1".to_string(),
+                "1".to_string(),
+            ),
+        ];
+
+        for (input, expected) in tests {
+            // Act
+            let value = eval(&input).unwrap();
+
+            // Assert
+            assert_eq!(value.inspect(), expected);
+        }
+    }
+
+    #[test]
+    fn try_catch_and_throw() {
+        // Arrange
+        let tests = vec![
+            ("try { 1 } catch (e) { 2 }", "1"),
+            ("try { 1 / 0 } catch (e) { e.message }", "division by zero"),
+            ("try { {\"a\": 1}.b } catch (e) { e.message }", "key not found: \"b\""),
+            ("try { throw \"boom\"; } catch (e) { e }", "boom"),
+            ("try { throw [1, 2]; } catch ([a, b]) { a + b }", "3"),
+            (
+                "try { throw {\"message\": \"bad\", \"code\": 7}; } catch (e) { e.code }",
+                "7",
+            ),
+            (
+                "fn f(x) { 1 / x } try { f(0) } catch (e) { e.stack }",
+                "[1:25 in f]",
+            ),
+            (
+                "fn f() { try { return 1; } catch (e) { 2 }; 3 } f()",
+                "1",
+            ),
+            (
+                "let mut cleaned = false; fn f() { try { return 1; } finally { cleaned = true; } } [f(), cleaned]",
+                "[1, true]",
+            ),
+            (
+                "let mut n = 0; try { n = 1; throw \"x\"; } catch (e) { n += 10 } finally { n += 100 }; n",
+                "111",
+            ),
+            (
+                "fn f() { try { 1 / 0 } finally { return \"cleaned\"; } } f()",
+                "cleaned",
+            ),
+            (
+                "let mut i = 0; while (true) { try { i += 1; if (i == 3) { break; } } catch (e) {} } i",
+                "3",
+            ),
+            (
+                "try { try { throw \"inner\"; } finally { 0 } } catch (e) { \"caught \" + e }",
+                "caught inner",
+            ),
+            (
+                "try { try { 1 / 0 } catch (e) { throw e; } } catch (e) { e.message }",
+                "division by zero",
+            ),
+            (
+                "let mut n = 0; let m = try { try { throw \"s\"; } catch ([a, b]) { 1 } finally { n = 1 } } catch (e) { e }; [n, m]",
+                "[1, s]",
+            ),
+            (
+                "try { try { 1 / 0 } catch ([a]) { 1 } } catch (e) { e.message }",
+                "division by zero",
+            ),
+        ];
+
+        // Act + Assert
+        assert_evaluates_to(tests);
+    }
+
+    #[test]
+    fn uncaught_throws_are_errors() {
+        // Arrange
+        let tests = vec![
+            ("throw \"boom\";", "boom"),
+            ("throw {\"message\": \"bad input\"};", "bad input"),
+            ("throw 42;", "42"),
+            ("try { throw 1; } catch (e) { throw e + 1; }", "2"),
+            ("try { throw \"boom\"; } catch ([a]) { a }", "boom"),
+            (
+                "try { throw {\"message\": \"bad\"}; } catch ({code}) { code }",
+                "bad",
+            ),
+        ];
+
+        for (input, expected) in tests {
+            // Act
+            let error = eval(input).unwrap_err();
+
+            // Assert
+            assert_eq!(error.kind, ErrorKind::Thrown, "{input}");
+            assert_eq!(error.message, expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn errors_record_the_call_stack() {
+        // Arrange
+        let input = "fn outer(x) {\n  inner(x)\n}\nfn inner(x) {\n  [1, 2].map(fn(y) { y / x })\n}\nouter((fn(y) { y / 0 })(1))";
+        let frame = |function: &str, line, column| Frame {
+            function: function.to_string(),
+            file: None,
+            position: Position::new(line, column),
+        };
+
+        // Act
+        let error = eval(input).unwrap_err();
+        let inner_error = eval(&input.replace("(fn(y) { y / 0 })(1)", "0")).unwrap_err();
+
+        // Assert
+        assert_eq!(error.message, "division by zero");
+        assert_eq!(error.stack, vec![frame("<anonymous>", 7, 7)]);
+        assert_eq!(
+            inner_error.message,
+            "member access not supported: ARRAY.map"
+        );
+        assert_eq!(
+            inner_error.stack,
+            vec![frame("inner", 2, 3), frame("outer", 7, 1)]
+        );
+        assert_eq!(
+            inner_error.traceback(),
+            "Traceback (most recent call last):\n  7:1 in outer\n  2:3 in inner"
+        );
+        assert_eq!(eval("1 / 0").unwrap_err().traceback(), "");
+    }
+
+    #[test]
+    fn tracebacks_shorten_recursion() {
+        // Arrange
+        let input = "fn down(n) { n == 0 ? n / 0 : down(n - 1) }\ndown(3)";
+
+        // Act
+        let error = eval(input).unwrap_err();
+
+        // Assert
+        assert_eq!(error.stack.len(), 4);
+        assert_eq!(
+            error.traceback(),
+            "Traceback (most recent call last):\n  2:1 in down\n  1:31 in down\n  [previous frame repeated 2 more times]"
+        );
+    }
+
+    #[test]
+    fn bindings_are_immutable_across_programs() {
+        // Arrange
+        let mut evaluator = Evaluator::new();
+        eval_with(&mut evaluator, "let x = 1; let mut y = 1;").unwrap();
+
+        // Act
+        let x = eval_with(&mut evaluator, "x = 2");
+        let y = eval_with(&mut evaluator, "y = 2");
+
+        // Assert
+        assert_eq!(
+            x.unwrap_err().message,
+            "cannot assign to immutable binding: x"
+        );
+        assert_eq!(y.unwrap().inspect(), "2");
+    }
+
+    #[test]
+    fn error_handling() {
+        // Arrange
+        let tests = vec![
+            ("5 + true;", "type mismatch: INTEGER + BOOLEAN"),
+            ("1.5 + \"a\"", "type mismatch: FLOAT + STRING"),
+            ("-\"a\"", "unknown operator: -STRING"),
+            ("-true", "unknown operator: -BOOLEAN"),
+            ("true + false; 5", "unknown operator: BOOLEAN + BOOLEAN"),
+            ("\"a\" - \"b\"", "unknown operator: STRING - STRING"),
+            ("foobar", "identifier not found: foobar"),
+            ("5 / 0", "division by zero"),
+            ("5 % 0", "division by zero"),
+            ("5()", "not a function: INTEGER"),
+            ("[1, 2][2]", "index out of range: 2 (length 2)"),
+            ("[1, 2][-1]", "index out of range: -1 (length 2)"),
+            ("{\"a\": 1}[\"b\"]", "key not found: \"b\""),
+            ("{[1]: 2}", "unusable as hash key: ARRAY"),
+            ("{\"a\": 1}[fn(x) { x }]", "unusable as hash key: FUNCTION"),
+            ("1[0]", "index operator not supported: INTEGER[INTEGER]"),
+            ("{\"a\": 1}.b", "key not found: \"b\""),
+            ("let n = 1; n.x", "member access not supported: INTEGER.x"),
+            ("for (x in 5) { x }", "cannot iterate over INTEGER"),
+            ("x = 5", "assignment to undeclared name: x"),
+            ("x += 5", "assignment to undeclared name: x"),
+            (
+                "let f = fn() { y = 1 }; f()",
+                "assignment to undeclared name: y",
+            ),
+            (
+                "let mut xs = [1]; xs[1] = 2",
+                "index out of range: 1 (length 1)",
+            ),
+            (
+                "let mut s = \"ab\"; s[0] = \"c\"",
+                "index assignment not supported: STRING[INTEGER]",
+            ),
+            (
+                "let mut x = 1; x += true",
+                "type mismatch: INTEGER + BOOLEAN",
+            ),
+            (
+                "let f = fn() { x = 2 }; let x = 1; f()",
+                "cannot assign to immutable binding: x",
+            ),
+            (
+                "0..1.5",
+                "range bounds must be integers, got INTEGER..FLOAT",
+            ),
+            (
+                "fn(x) { x }()",
+                "wrong number of arguments to anonymous function: expected 1, got 0",
+            ),
+            (
+                "fn add(x, y) { x + y } add(1, 2, 3)",
+                "wrong number of arguments to add: expected 2, got 3",
+            ),
+            (
+                "let f = fn(x, y = 1) { x }; f()",
+                "wrong number of arguments to f: expected 1 to 2, got 0",
+            ),
+            (
+                "let f = fn(x, y = 1) { x }; f(1, 2, 3)",
+                "wrong number of arguments to f: expected 1 to 2, got 3",
+            ),
+            (
+                "fn f(x, ...rest) { x } f()",
+                "wrong number of arguments to f: expected at least 1, got 0",
+            ),
+            ("fn f(x) { x } f(...5)", "cannot iterate over INTEGER"),
+            ("match (3) { 1 => 1, 2 => 2 }", "no match arm matched: 3"),
+            (
+                "let [a, b, c] = [1, 2]",
+                "cannot destructure [1, 2]: expected 3 elements, got 2",
+            ),
+            (
+                "let [a, ...rest] = []",
+                "cannot destructure []: expected at least 1 element, got 0",
+            ),
+            (
+                "let [a] = 5",
+                "cannot destructure 5: expected an array, got INTEGER",
+            ),
+            (
+                "let {name, age} = {\"name\": 1}",
+                "cannot destructure {name: 1}: missing key \"age\"",
+            ),
+            (
+                "let {k} = [1]",
+                "cannot destructure [1]: expected a hash, got ARRAY",
+            ),
+            (
+                "let [0, x] = [1, 2]",
+                "cannot destructure [1, 2]: expected 0, got 1",
+            ),
+            (
+                "fn f([a, b]) { a } f([1])",
+                "cannot destructure [1]: expected 2 elements, got 1",
+            ),
+        ];
+
+        for (input, expected) in tests {
+            // Act
+            let error = eval(input).unwrap_err();
+
+            // Assert
+            assert_eq!(error.message, expected, "{input}");
+        }
+    }
+}
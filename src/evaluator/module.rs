@@ -0,0 +1,397 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::lexer::Lexer;
+use crate::object::{Env, Environment, Module, Object};
+use crate::parser::ast::{Program, Statement};
+use crate::parser::Parser;
+
+use super::{Error, Evaluator, Result};
+
+impl Evaluator {
+    /// Evaluates the file at `path` in the global environment. Imports in it
+    /// are resolved relative to the file.
+    pub fn eval_file(&mut self, path: impl AsRef<Path>) -> Result<Object> {
+        let path = path.as_ref();
+        let path = path
+            .canonicalize()
+            .map_err(|error| Error::new(format!("cannot read {}: {error}", path.display())))?;
+        let program = self.parse_file(&path)?;
+
+        self.reset_usage();
+        let env = self.env.clone();
+        self.eval_in_file(path, &program, &env)
+    }
+
+    /// Loads the module at `path`, evaluating it the first time it is
+    /// imported and reusing the cached exports after that.
+    pub(super) fn import(&mut self, path: &str) -> Result<Module> {
+        let base = match self.loading.last().and_then(|file| file.parent()) {
+            Some(directory) => directory.to_path_buf(),
+            None => std::env::current_dir()
+                .map_err(|error| Error::new(format!("cannot import {path:?}: {error}")))?,
+        };
+        let file = base.join(path);
+        // Checked before looking at the file, so a denied import does not
+        // reveal whether it exists.
+        self.capabilities.borrow().check_import(&file)?;
+        let path = file
+            .canonicalize()
+            .map_err(|error| Error::new(format!("cannot import {path:?}: {error}")))?;
+
+        if let Some(module) = self.modules.get(&path) {
+            return Ok(module.clone());
+        }
+
+        if let Some(start) = self.loading.iter().position(|file| *file == path) {
+            let cycle = self.loading[start..]
+                .iter()
+                .chain([&path])
+                .map(|file| display_path(file))
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            return Err(Error::new(format!("import cycle: {cycle}")));
+        }
+
+        let program = self.parse_file(&path)?;
+        let env = Environment::enclosed(&self.builtins);
+
+        self.eval_in_file(path.clone(), &program, &env)?;
+
+        let env = env.borrow();
+        let exports = exported_names(&program)
+            .into_iter()
+            .filter_map(|name| env.get(&name).map(|value| (name, value)))
+            .collect::<BTreeMap<_, _>>();
+
+        let module = Module { path, exports };
+        self.modules.insert(module.path.clone(), module.clone());
+
+        Ok(module)
+    }
+
+    /// Returns the parser warnings for files read by `eval_file` and imports
+    /// since the last call, each prefixed with its file.
+    pub fn take_warnings(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    fn parse_file(&mut self, path: &Path) -> Result<Program> {
+        let source = fs::read_to_string(path)
+            .map_err(|error| Error::new(format!("cannot read {}: {error}", display_path(path))))?;
+
+        let mut parser = Parser::new(Lexer::new(&source));
+        let program = parser.parse_program();
+        let in_file = |message: &String| format!("{}:{message}", display_path(path));
+
+        self.warnings.extend(parser.warnings.iter().map(in_file));
+
+        if !parser.errors.is_empty() {
+            let errors = parser.errors.iter().map(in_file).collect::<Vec<_>>();
+            return Err(Error::new(errors.join("\n")));
+        }
+
+        Ok(program)
+    }
+
+    /// Evaluates `program`, which was read from `path`.
+    fn eval_in_file(&mut self, path: PathBuf, program: &Program, env: &Env) -> Result<Object> {
+        let outer_file = self.file.replace(Rc::new(path.clone()));
+        self.loading.push(path);
+        let result = self.eval_program_in(program, env);
+        self.loading.pop();
+        self.file = outer_file;
+
+        result
+    }
+}
+
+fn exported_names(program: &Program) -> Vec<String> {
+    let mut names = vec![];
+
+    for statement in &program.statements {
+        let Statement::Export { statement, .. } = statement else {
+            continue;
+        };
+        match statement.as_ref() {
+            Statement::Let { pattern, .. } => names.extend(
+                pattern
+                    .bindings()
+                    .into_iter()
+                    .map(|name| name.value.clone()),
+            ),
+            Statement::Function { name, .. } => names.push(name.value.clone()),
+            _ => {}
+        }
+    }
+
+    names
+}
+
+/// Paths under the working directory are shown relative to it.
+pub(super) fn display_path(path: &Path) -> String {
+    std::env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok())
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use tempfile::TempDir;
+
+    use crate::object::ObjectType;
+
+    use super::super::{Capabilities, PathAccess};
+    use super::Evaluator;
+
+    /// Writes `files` into a fresh temp directory, which is deleted when
+    /// the returned handle is dropped.
+    fn write_files(name: &str, files: &[(&str, &str)]) -> TempDir {
+        let directory = tempfile::Builder::new()
+            .prefix(&format!("gibbon-{name}-"))
+            .tempdir()
+            .unwrap();
+
+        for (path, source) in files {
+            let path = directory.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, source).unwrap();
+        }
+
+        directory
+    }
+
+    /// An evaluator allowed to import modules from `directory`.
+    fn importing_from(directory: &Path) -> Evaluator {
+        let mut evaluator = Evaluator::new();
+        evaluator.set_capabilities(Capabilities {
+            import: PathAccess::Only(vec![directory.to_path_buf()]),
+            ..Capabilities::default()
+        });
+        evaluator
+    }
+
+    #[test]
+    fn imports_resolve_relative_to_the_importing_file() {
+        // Arrange
+        let files = write_files(
+            "relative",
+            &[
+                ("main.gb", "import \"lib/math.gb\" as math; math.square(math.base)"),
+                (
+                    "lib/math.gb",
+                    "import \"consts.gb\" as consts; export let base = consts.three; export fn square(x) { x * x }",
+                ),
+                ("lib/consts.gb", "export let three = 3; let hidden = 4;"),
+            ],
+        );
+        let directory = files.path();
+
+        // Act
+        let result = importing_from(directory).eval_file(directory.join("main.gb"));
+
+        // Assert
+        assert_eq!(result.unwrap().inspect(), "9");
+    }
+
+    #[test]
+    fn modules_are_evaluated_once() {
+        // Arrange
+        let files = write_files(
+            "cache",
+            &[
+                (
+                    "main.gb",
+                    "import \"a.gb\" as a; import \"b.gb\" as b; a.counter() + b.counter()",
+                ),
+                (
+                    "a.gb",
+                    "import \"counter.gb\" as c; export let counter = c.made;",
+                ),
+                (
+                    "b.gb",
+                    "import \"counter.gb\" as c; export let counter = c.made;",
+                ),
+                ("counter.gb", "export let made = fn() { 1 };"),
+            ],
+        );
+        let directory = files.path();
+        let mut evaluator = importing_from(directory);
+
+        // Act
+        let result = evaluator.eval_file(directory.join("main.gb"));
+
+        // Assert
+        assert_eq!(result.unwrap().inspect(), "2");
+        assert_eq!(evaluator.modules.len(), 3);
+    }
+
+    #[test]
+    fn warnings_name_their_files() {
+        // Arrange
+        let files = write_files(
+            "warnings",
+            &[
+                ("main.gb", "import \"lib.gb\" as lib;\nmatch (1) { 1 => 2 }"),
+                ("lib.gb", "export fn f(x) {\n  match (x) { [] => 0 }\n}"),
+            ],
+        );
+        let directory = files.path();
+        let mut evaluator = importing_from(directory);
+
+        // Act
+        evaluator.eval_file(directory.join("main.gb")).unwrap();
+        let warnings = evaluator.take_warnings();
+
+        // Assert
+        let warnings = warnings
+            .iter()
+            .map(|warning| warning.rsplit('/').next().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            warnings,
+            [
+                "main.gb:2:1: non-exhaustive match: add a `_` arm to handle any other value",
+                "lib.gb:2:3: non-exhaustive match: add a `_` arm to handle any other value",
+            ]
+        );
+        assert!(evaluator.take_warnings().is_empty());
+    }
+
+    #[test]
+    fn stack_frames_name_their_files() {
+        // Arrange
+        let files = write_files(
+            "frames",
+            &[
+                ("main.gb", "import \"lib.gb\" as lib;\nlib.fail()"),
+                (
+                    "lib.gb",
+                    "export fn fail() {\n  explode(1)\n}\nfn explode(x) { x + true }",
+                ),
+            ],
+        );
+        let directory = files.path();
+
+        // Act
+        let error = importing_from(directory)
+            .eval_file(directory.join("main.gb"))
+            .unwrap_err();
+
+        // Assert
+        let files = error
+            .stack
+            .iter()
+            .map(|frame| frame.file.as_ref().unwrap().file_name().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(files, ["lib.gb", "main.gb"]);
+        assert_eq!(
+            error.stack[0].to_string().rsplit('/').next(),
+            Some("lib.gb:2:3 in explode")
+        );
+    }
+
+    #[test]
+    fn module_errors() {
+        // Arrange
+        let files = write_files(
+            "errors",
+            &[
+                ("cycle.gb", "import \"a.gb\" as a;"),
+                ("a.gb", "import \"b.gb\" as b;"),
+                ("b.gb", "import \"a.gb\" as a;"),
+                ("missing.gb", "import \"nowhere.gb\" as n;"),
+                ("private.gb", "import \"lib.gb\" as lib; lib.hidden"),
+                ("lib.gb", "export let shown = 1; let hidden = 2;"),
+                ("broken.gb", "import \"bad.gb\" as bad;"),
+                ("bad.gb", "let = 1;"),
+            ],
+        );
+        let directory = files.path();
+        let a = directory.join("a.gb").canonicalize().unwrap();
+        let b = directory.join("b.gb").canonicalize().unwrap();
+        let lib = directory.join("lib.gb").canonicalize().unwrap();
+        let bad = directory.join("bad.gb").canonicalize().unwrap();
+        let tests = vec![
+            (
+                "cycle.gb",
+                format!(
+                    "import cycle: {} -> {} -> {}",
+                    a.display(),
+                    b.display(),
+                    a.display()
+                ),
+            ),
+            (
+                "missing.gb",
+                "cannot import \"nowhere.gb\": No such file or directory (os error 2)".to_string(),
+            ),
+            (
+                "private.gb",
+                format!("module {} has no export hidden", lib.display()),
+            ),
+            (
+                "broken.gb",
+                format!(
+                    "{}:1:5: expected a pattern, got Assign instead",
+                    bad.display()
+                ),
+            ),
+        ];
+
+        for (file, expected) in tests {
+            // Act
+            let error = importing_from(directory)
+                .eval_file(directory.join(file))
+                .unwrap_err();
+
+            // Assert
+            assert_eq!(error.message, expected, "{file}");
+        }
+    }
+
+    #[test]
+    fn imports_need_the_import_capability() {
+        // Arrange
+        let files = write_files(
+            "denied",
+            &[
+                ("app/main.gb", "import \"../secret.gb\" as s; s.token"),
+                ("app/missing.gb", "import \"../nowhere.gb\" as n;"),
+                ("secret.gb", "export let token = \"hunter2\";"),
+            ],
+        );
+        let directory = files.path();
+        let app = directory.join("app");
+        let tests = vec![
+            ("main.gb", Evaluator::new(), "secret.gb"),
+            ("main.gb", importing_from(&app), "secret.gb"),
+            ("missing.gb", importing_from(&app), "nowhere.gb"),
+        ];
+
+        for (file, mut evaluator, denied) in tests {
+            // Act
+            let error = evaluator.eval_file(app.join(file)).unwrap_err();
+
+            // Assert
+            let expected = format!(
+                "permission denied: import access to {:?}",
+                app.canonicalize()
+                    .unwrap()
+                    .join("..")
+                    .join(denied)
+                    .display()
+                    .to_string()
+            );
+            assert_eq!(error.message, expected, "{file}");
+        }
+    }
+}
@@ -0,0 +1,286 @@
+//! A facade for embedding gibbon in a Rust program, so host code never needs
+//! to drive the lexer, parser and evaluator itself.
+
+use std::path::Path;
+
+use crate::evaluator::{Capabilities, Error, Evaluator, Limits, Overflow};
+use crate::lexer::Lexer;
+use crate::object::{Builtin, TypedFunction};
+pub use crate::object::{Value, ValueKind};
+use crate::parser::Parser;
+
+/// Runs gibbon source, keeping global bindings between calls.
+///
+/// ```
+/// use gibbon::Interpreter;
+///
+/// let mut interpreter = Interpreter::new();
+/// interpreter.set_global("limit", 3);
+/// let value = interpreter.eval_str("limit * 2").unwrap();
+/// assert_eq!(i64::try_from(value).unwrap(), 6);
+/// ```
+#[derive(Default)]
+pub struct Interpreter {
+    evaluator: Evaluator,
+    warnings: Vec<String>,
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        Interpreter {
+            evaluator: Evaluator::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn set_overflow(&mut self, overflow: Overflow) {
+        self.evaluator.set_overflow(overflow);
+    }
+
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.evaluator.set_limits(limits);
+    }
+
+    /// Sets which side effects builtins such as `puts` and `read_file` may
+    /// have. Scripts can have none unless this is called.
+    pub fn set_capabilities(&mut self, capabilities: Capabilities) {
+        self.evaluator.set_capabilities(capabilities);
+    }
+
+    /// Parses and evaluates `source`. Parser errors are joined into a single
+    /// error, one per line.
+    pub fn eval_str(&mut self, source: &str) -> Result<Value, Error> {
+        let mut parser = Parser::new(Lexer::new(source));
+        let program = parser.parse_program();
+        self.warnings = std::mem::take(&mut parser.warnings);
+
+        if !parser.errors.is_empty() {
+            return Err(Error::new(parser.errors.join("\n")));
+        }
+
+        let result = self.evaluator.eval_program(&program);
+        self.warnings.extend(self.evaluator.take_warnings());
+        result.map(Value)
+    }
+
+    pub fn eval_file(&mut self, path: impl AsRef<Path>) -> Result<Value, Error> {
+        let result = self.evaluator.eval_file(path);
+        self.warnings = self.evaluator.take_warnings();
+        result.map(Value)
+    }
+
+    /// The parser warnings from the last `eval_str` or `eval_file`, including
+    /// those from imported files.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Binds `name` for later scripts. Scripts cannot assign to it, but a
+    /// script's own `let name` shadows it from then on, including for
+    /// `get_global`.
+    pub fn set_global(&mut self, name: impl Into<String>, value: impl Into<Value>) {
+        self.evaluator.set_global(name, value.into().0);
+    }
+
+    pub fn get_global(&self, name: &str) -> Option<Value> {
+        self.evaluator.get_global(name).map(Value)
+    }
+
+    /// Exposes a Rust closure to scripts as a function called `name`. The
+    /// closure receives the arguments as they were passed.
+    pub fn register_fn(
+        &mut self,
+        name: impl Into<String>,
+        function: impl Fn(&[Value]) -> Result<Value, Error> + 'static,
+    ) {
+        self.evaluator
+            .define_builtin(Builtin::new(name, move |arguments| {
+                let arguments = arguments.iter().cloned().map(Value).collect::<Vec<_>>();
+                function(&arguments).map(|value| value.0)
+            }));
+    }
+
+    /// Like `register_fn`, but the closure takes typed parameters such as
+    /// `|id: i64, active: bool|`. Calls with the wrong number or types of
+    /// arguments fail before the closure runs.
+    pub fn register_typed<Args, F: TypedFunction<Args> + 'static>(
+        &mut self,
+        name: impl Into<String>,
+        function: F,
+    ) {
+        self.evaluator
+            .define_builtin(Builtin::typed(name, function));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{Error, Interpreter, Value, ValueKind};
+
+    #[test]
+    fn globals_are_shared_with_scripts() {
+        // Arrange
+        let mut interpreter = Interpreter::new();
+        interpreter.set_global("names", vec!["ann", "bo"]);
+        interpreter.set_global("ages", HashMap::from([("ann".to_string(), 30_i64)]));
+
+        // Act
+        interpreter
+            .eval_str("let greeting = \"hi \" + names[1]; let age = ages.ann + 1;")
+            .unwrap();
+
+        // Assert
+        let greeting = String::try_from(interpreter.get_global("greeting").unwrap());
+        let age = i64::try_from(interpreter.get_global("age").unwrap());
+        assert_eq!(greeting.unwrap(), "hi bo");
+        assert_eq!(age.unwrap(), 31);
+        assert!(interpreter.get_global("missing").is_none());
+    }
+
+    #[test]
+    fn registered_functions() {
+        // Arrange
+        let users = HashMap::from([(42_i64, "ann".to_string())]);
+        let mut interpreter = Interpreter::new();
+        interpreter.register_fn("count", |arguments| Ok((arguments.len() as i64).into()));
+        interpreter.register_typed("lookup_user", move |id: i64| {
+            users
+                .get(&id)
+                .cloned()
+                .ok_or_else(|| Error::new(format!("no user {id}")))
+        });
+        interpreter.register_typed("join", |parts: Vec<String>, separator: String| {
+            Ok(parts.join(&separator))
+        });
+        interpreter.register_typed("kind", |value: Value| Ok(value.kind().to_string()));
+        interpreter.register_typed("first", |values: Vec<Value>| Ok(values.into_iter().next()));
+        let tests = vec![
+            ("count(1, \"a\", [])", Ok("3")),
+            ("lookup_user(42)", Ok("ann")),
+            ("let find = lookup_user; find(42)", Ok("ann")),
+            ("join([\"a\", \"b\"], \"-\")", Ok("a-b")),
+            ("lookup_user", Ok("builtin lookup_user")),
+            ("kind([1])", Ok("ARRAY")),
+            ("first([\"a\", 2])", Ok("a")),
+            ("first([])", Ok("null")),
+            ("lookup_user(7)", Err("no user 7")),
+            (
+                "lookup_user(\"42\")",
+                Err("argument 1 to lookup_user: expected INTEGER, got STRING"),
+            ),
+            (
+                "lookup_user()",
+                Err("wrong number of arguments to lookup_user: expected 1, got 0"),
+            ),
+            (
+                "first(1)",
+                Err("argument 1 to first: expected ARRAY, got INTEGER"),
+            ),
+            (
+                "join([1], \"-\")",
+                Err("argument 1 to join: expected STRING, got INTEGER"),
+            ),
+        ];
+
+        for (input, expected) in tests {
+            // Act
+            let result = interpreter.eval_str(input);
+
+            // Assert
+            let result = result.map(|value| value.to_string());
+            let result = result.as_deref().map_err(|error| error.message.as_str());
+            assert_eq!(result, expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn eval_str_reports_errors() {
+        // Arrange
+        let mut interpreter = Interpreter::new();
+        interpreter.set_global("limit", 1_i64);
+
+        // Act
+        let parse_error = interpreter.eval_str("let = 1; let x 2;").unwrap_err();
+        let runtime_error = interpreter.eval_str("limit = 2").unwrap_err();
+        let nested_error = interpreter
+            .eval_str("fn f() { limit = 2 } f()")
+            .unwrap_err();
+        let thrown_error = interpreter.eval_str("throw [1, 2]").unwrap_err();
+
+        // Assert
+        assert_eq!(
+            parse_error.message,
+            "1:5: expected a pattern, got Assign instead\n1:16: expected next token to be Assign, got Int instead"
+        );
+        assert_eq!(
+            runtime_error.message,
+            "cannot assign to immutable binding: limit"
+        );
+        assert_eq!(nested_error.message, runtime_error.message);
+        let thrown = thrown_error.thrown.map(|value| *value).unwrap();
+        assert_eq!(thrown.kind(), ValueKind::Array);
+        assert_eq!(Vec::<i64>::try_from(thrown).unwrap(), [1, 2]);
+    }
+
+    #[test]
+    fn warnings_are_kept_until_the_next_eval() {
+        // Arrange
+        let mut interpreter = Interpreter::new();
+
+        // Act
+        interpreter.eval_str("match (1) { 1 => 2 }").unwrap();
+        let warned = interpreter.warnings().to_vec();
+        interpreter.eval_str("1").unwrap();
+
+        // Assert
+        assert_eq!(
+            warned,
+            ["1:1: non-exhaustive match: add a `_` arm to handle any other value"]
+        );
+        assert!(interpreter.warnings().is_empty());
+    }
+
+    #[test]
+    fn values() {
+        // Arrange
+        let tests = vec![
+            (Value::from(3_i64), ValueKind::Integer, "3"),
+            (Value::from(1.5), ValueKind::Float, "1.5"),
+            (Value::from("hi"), ValueKind::String, "hi"),
+            (Value::from(vec![true]), ValueKind::Array, "[true]"),
+        ];
+
+        for (value, kind, expected) in tests {
+            // Act
+            let inspected = value.to_string();
+
+            // Assert
+            assert_eq!(value.kind(), kind);
+            assert_eq!(inspected, expected);
+            assert!(!value.is_null());
+        }
+        let mut interpreter = Interpreter::new();
+        assert!(interpreter.eval_str("let x = 1;").unwrap().is_null());
+        let error = bool::try_from(Value::from(1_i64)).unwrap_err();
+        assert_eq!(error.message, "expected BOOLEAN, got INTEGER");
+    }
+
+    #[test]
+    fn scripts_shadow_host_globals() {
+        // Arrange
+        let mut interpreter = Interpreter::new();
+        interpreter.set_global("limit", 1_i64);
+
+        // Act
+        let before = interpreter.eval_str("limit").unwrap();
+        let shadowed = interpreter.eval_str("let mut limit = 99; limit += 1; limit");
+        let after = interpreter.get_global("limit").unwrap();
+
+        // Assert
+        assert_eq!(before.to_string(), "1");
+        assert_eq!(shadowed.unwrap().to_string(), "100");
+        assert_eq!(after.to_string(), "100");
+    }
+}
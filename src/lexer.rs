@@ -1,73 +1,168 @@
-use crate::token::{lookup_ident, Kind, Token};
+use std::num::{ParseFloatError, ParseIntError};
 
-pub struct Lexer {
-    input: String,
+use crate::token::{lookup_ident, Kind, Position, Reason, Span, Token};
+
+pub struct Lexer<'a> {
+    input: &'a str,
     position: usize,
     read_position: usize,
     ch: char,
+    line: usize,
+    column: usize,
+    token_start: usize,
+    token_position: Position,
 }
 
-impl Lexer {
-    pub fn new(input: String) -> Lexer {
+impl<'a> Lexer<'a> {
+    pub fn new(input: &'a str) -> Lexer<'a> {
         let mut lexer = Lexer {
             input,
             position: 0,
             read_position: 0,
             ch: '\0',
+            line: 1,
+            column: 1,
+            token_start: 0,
+            token_position: Position::new(1, 1),
         };
         lexer.read_char();
         lexer
     }
 
-    pub fn next_token(&mut self) -> Token {
+    pub fn next_token(&mut self) -> Token<'a> {
         self.skip_whitespace();
 
-        let token = match self.ch {
+        self.token_start = self.position;
+        self.token_position = Position::new(self.line, self.column);
+
+        let kind = match self.ch {
             '=' => {
                 if self.peek_char() == Some('=') {
                     self.read_char();
-                    Token::new(Kind::Eq, "==".to_string())
+                    Kind::Eq
+                } else if self.peek_char() == Some('>') {
+                    self.read_char();
+                    Kind::FatArrow
+                } else {
+                    Kind::Assign
+                }
+            }
+            '+' => {
+                if self.peek_char() == Some('=') {
+                    self.read_char();
+                    Kind::PlusAssign
+                } else {
+                    Kind::Plus
+                }
+            }
+            '-' => {
+                if self.peek_char() == Some('=') {
+                    self.read_char();
+                    Kind::MinusAssign
                 } else {
-                    Token::new(Kind::Assign, self.ch.to_string())
+                    Kind::Minus
+                }
+            }
+            '*' => {
+                if self.peek_char() == Some('=') {
+                    self.read_char();
+                    Kind::AsterixAssign
+                } else {
+                    Kind::Asterix
+                }
+            }
+            '/' => {
+                if self.peek_char() == Some('=') {
+                    self.read_char();
+                    Kind::SlashAssign
+                } else {
+                    Kind::Slash
                 }
             }
-            '+' => Token::new(Kind::Plus, self.ch.to_string()),
-            '-' => Token::new(Kind::Minus, self.ch.to_string()),
-            '*' => Token::new(Kind::Asterix, self.ch.to_string()),
-            '/' => Token::new(Kind::Slash, self.ch.to_string()),
             '!' => {
                 if self.peek_char() == Some('=') {
                     self.read_char();
-                    Token::new(Kind::Ne, "!=".to_string())
+                    Kind::Ne
                 } else {
-                    Token::new(Kind::Bang, self.ch.to_string())
+                    Kind::Bang
                 }
             }
-            '<' => Token::new(Kind::Lt, self.ch.to_string()),
-            '>' => Token::new(Kind::Gt, self.ch.to_string()),
-            ';' => Token::new(Kind::SemiColon, self.ch.to_string()),
-            ',' => Token::new(Kind::Comma, self.ch.to_string()),
-            '(' => Token::new(Kind::LParen, self.ch.to_string()),
-            ')' => Token::new(Kind::RParen, self.ch.to_string()),
-            '{' => Token::new(Kind::LBrace, self.ch.to_string()),
-            '}' => Token::new(Kind::RBrace, self.ch.to_string()),
-            '\0' => Token::new(Kind::Eof, "".to_string()),
+            '%' => Kind::Percent,
+            '<' => {
+                if self.peek_char() == Some('=') {
+                    self.read_char();
+                    Kind::LtEq
+                } else {
+                    Kind::Lt
+                }
+            }
+            '>' => {
+                if self.peek_char() == Some('=') {
+                    self.read_char();
+                    Kind::GtEq
+                } else {
+                    Kind::Gt
+                }
+            }
+            '&' if self.peek_char() == Some('&') => {
+                self.read_char();
+                Kind::And
+            }
+            '|' if self.peek_char() == Some('|') => {
+                self.read_char();
+                Kind::Or
+            }
+            '.' if self.peek_char() == Some('.') => {
+                self.read_char();
+                if self.peek_char() == Some('=') {
+                    self.read_char();
+                    Kind::DotDotEq
+                } else if self.peek_char() == Some('.') {
+                    self.read_char();
+                    Kind::DotDotDot
+                } else {
+                    Kind::DotDot
+                }
+            }
+            '?' => Kind::Question,
+            '.' => Kind::Dot,
+            ';' => Kind::SemiColon,
+            ':' => Kind::Colon,
+            ',' => Kind::Comma,
+            '(' => Kind::LParen,
+            ')' => Kind::RParen,
+            '{' => Kind::LBrace,
+            '}' => Kind::RBrace,
+            '[' => Kind::LBracket,
+            ']' => Kind::RBracket,
+            '"' => return self.read_string(),
+            '\0' if self.at_end() => Kind::Eof,
             c => {
                 if identifier_character(&c) {
                     let literal = self.read_identifier();
-                    let ident = lookup_ident(&literal);
-                    return Token::new(ident, literal);
+                    return self.token(lookup_ident(literal));
                 } else if c.is_ascii_digit() {
-                    return Token::new(Kind::Int, self.read_number());
+                    let kind = self.read_number();
+                    return self.token(kind);
                 } else {
-                    return Token::new(Kind::Illegal, "".to_string());
+                    self.read_char();
+                    return self.token(Kind::Illegal(Reason::UnexpectedChar(c)));
                 }
             }
         };
 
         self.read_char();
 
-        token
+        self.token(kind)
+    }
+
+    fn token(&self, kind: Kind) -> Token<'a> {
+        self.token_with_literal(kind, &self.input[self.token_start..self.position])
+    }
+
+    fn token_with_literal(&self, kind: Kind, literal: &'a str) -> Token<'a> {
+        let span = Span::new(self.token_start, self.position);
+        Token::new(kind, literal, span, self.token_position)
     }
 
     fn skip_whitespace(&mut self) {
@@ -76,41 +171,234 @@ impl Lexer {
         }
     }
 
+    fn at_end(&self) -> bool {
+        self.position >= self.input.len()
+    }
+
     fn read_char(&mut self) {
-        self.ch = self.input.chars().nth(self.read_position).unwrap_or('\0');
-        self.position = self.read_position;
-        self.read_position += 1;
+        if self.read_position > 0 {
+            if self.ch == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+
+        self.position = self.read_position.min(self.input.len());
+        self.ch = self.input[self.position..].chars().next().unwrap_or('\0');
+        self.read_position = self.position + self.ch.len_utf8();
     }
 
-    fn read_identifier(&mut self) -> String {
+    fn read_identifier(&mut self) -> &'a str {
         let position = self.position;
         while identifier_character(&self.ch) {
             self.read_char();
         }
-        self.input[position..self.position].to_string()
+        &self.input[position..self.position]
     }
 
-    fn read_number(&mut self) -> String {
+    /// Reads a number literal. Integers may have a `0x`, `0b` or `0o` prefix,
+    /// decimal numbers with a fraction or exponent are floats, and both allow
+    /// `_` separators between digits.
+    fn read_number(&mut self) -> Kind {
         let position = self.position;
-        while self.ch.is_ascii_digit() {
+        let radix = match (self.ch, self.peek_char()) {
+            ('0', Some('x')) => 16,
+            ('0', Some('o')) => 8,
+            ('0', Some('b')) => 2,
+            _ => 10,
+        };
+
+        if radix != 10 {
+            self.read_char();
+            self.read_char();
+        }
+
+        let mut kind = Kind::Int;
+        let mut has_digits = self.read_digits(radix);
+
+        if radix == 10 {
+            if self.ch == '.' && self.peek_char().is_some_and(|c| c.is_ascii_digit()) {
+                kind = Kind::Float;
+                self.read_char();
+                self.read_digits(radix);
+            }
+
+            if matches!(self.ch, 'e' | 'E') {
+                kind = Kind::Float;
+                self.read_char();
+                if matches!(self.ch, '+' | '-') {
+                    self.read_char();
+                }
+                has_digits = self.read_digits(radix);
+            }
+        }
+
+        let mut invalid_digit = None;
+        while self.ch.is_ascii_alphanumeric() || self.ch == '_' {
+            invalid_digit = invalid_digit.or(Some(self.ch));
             self.read_char();
         }
-        self.input[position..self.position].to_string()
+
+        if let Some(c) = invalid_digit {
+            Kind::Illegal(Reason::InvalidDigit(c))
+        } else if !has_digits {
+            Kind::Illegal(Reason::MissingDigits)
+        } else if has_misplaced_separator(&self.input[position..self.position], radix) {
+            Kind::Illegal(Reason::MisplacedSeparator)
+        } else if kind == Kind::Int && parse_integer(&self.input[position..self.position]).is_err()
+        {
+            Kind::Illegal(Reason::IntegerOverflow)
+        } else {
+            kind
+        }
+    }
+
+    /// Reads digits of the given radix and `_` separators, returning whether
+    /// there was at least one digit.
+    fn read_digits(&mut self, radix: u32) -> bool {
+        let mut has_digits = false;
+        while self.ch.is_digit(radix) || self.ch == '_' {
+            has_digits |= self.ch != '_';
+            self.read_char();
+        }
+        has_digits
+    }
+
+    /// Reads a double quoted string. The token literal is the raw text between
+    /// the quotes, see [`unescape`] for its value.
+    fn read_string(&mut self) -> Token<'a> {
+        self.read_char();
+        let position = self.position;
+        let mut invalid_escape = None;
+
+        while self.ch != '"' {
+            if self.at_end() {
+                return self.token(Kind::Illegal(Reason::UnterminatedString));
+            }
+
+            if self.ch == '\\' {
+                self.read_char();
+                if !self.at_end() && !is_escape(self.ch) && invalid_escape.is_none() {
+                    invalid_escape = Some(self.ch);
+                }
+            }
+
+            if !self.at_end() {
+                self.read_char();
+            }
+        }
+
+        let literal = &self.input[position..self.position];
+        self.read_char();
+
+        match invalid_escape {
+            Some(c) => self.token(Kind::Illegal(Reason::InvalidEscape(c))),
+            None => self.token_with_literal(Kind::Str, literal),
+        }
+    }
+
+    fn peek_char(&self) -> Option<char> {
+        self.input.get(self.read_position..)?.chars().next()
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.next_token();
+        if token.kind == Kind::Eof {
+            None
+        } else {
+            Some(token)
+        }
+    }
+}
+
+/// Lexes the whole of `input`, stopping before the `Eof` token.
+pub fn tokenize(input: &str) -> Vec<Token<'_>> {
+    Lexer::new(input).collect()
+}
+
+/// Whether a `_` in the number literal `literal` is missing a digit on
+/// either side of it.
+fn has_misplaced_separator(literal: &str, radix: u32) -> bool {
+    literal.char_indices().any(|(i, c)| {
+        let before = literal[..i].chars().next_back();
+        let after = literal[i + 1..].chars().next();
+        let is_digit = |c: Option<char>| c.is_some_and(|c| c.is_digit(radix));
+        c == '_' && !(is_digit(before) && is_digit(after))
+    })
+}
+
+/// Parses the literal of an `Int` token, honouring its radix prefix and
+/// ignoring `_` separators.
+pub fn parse_integer(literal: &str) -> Result<i64, ParseIntError> {
+    let (radix, digits) = split_radix(literal);
+    i64::from_str_radix(&digits.replace('_', ""), radix)
+}
+
+/// Parses the literal of an `Int` token as if it had a minus sign in front.
+/// This lets `-9223372036854775808` parse, though its digits alone overflow.
+pub fn parse_negative_integer(literal: &str) -> Result<i64, ParseIntError> {
+    let (radix, digits) = split_radix(literal);
+    i64::from_str_radix(&format!("-{}", digits.replace('_', "")), radix)
+}
+
+fn split_radix(literal: &str) -> (u32, &str) {
+    match literal.get(..2) {
+        Some("0x") => (16, &literal[2..]),
+        Some("0o") => (8, &literal[2..]),
+        Some("0b") => (2, &literal[2..]),
+        _ => (10, literal),
     }
+}
+
+/// Parses the literal of a `Float` token, ignoring `_` separators.
+pub fn parse_float(literal: &str) -> Result<f64, ParseFloatError> {
+    literal.replace('_', "").parse()
+}
 
-    fn peek_char(&mut self) -> Option<char> {
-        self.input.chars().nth(self.read_position)
+/// Resolves the escape sequences in the literal of a `Str` token.
+pub fn unescape(literal: &str) -> String {
+    let mut value = String::with_capacity(literal.len());
+    let mut chars = literal.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('t') => value.push('\t'),
+            Some('r') => value.push('\r'),
+            Some('0') => value.push('\0'),
+            Some(c) => value.push(c),
+            None => {}
+        }
     }
+
+    value
 }
 
 fn identifier_character(c: &char) -> bool {
     c.is_ascii_alphabetic() || c == &'_'
 }
 
+fn is_escape(c: char) -> bool {
+    matches!(c, 'n' | 't' | 'r' | '0' | '"' | '\\')
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::lexer::Lexer;
-    use crate::token::Kind;
+    use crate::lexer::{
+        parse_float, parse_integer, parse_negative_integer, tokenize, unescape, Lexer,
+    };
+    use crate::token::{Kind, Position, Reason, Span};
 
     #[test]
     fn single_chars() {
@@ -129,7 +417,7 @@ mod tests {
         ];
 
         // Act
-        let mut lexer = Lexer::new(input);
+        let mut lexer = Lexer::new(&input);
 
         // Assert
         for case in cases {
@@ -241,6 +529,514 @@ mod tests {
             (Kind::Eof, ""),
         ];
 
+        // Act
+        let mut lexer = Lexer::new(&input);
+
+        // Assert
+        for case in cases {
+            let token = lexer.next_token();
+            assert_eq!(token.kind, case.0);
+            assert_eq!(token.literal, case.1);
+        }
+    }
+
+    #[test]
+    fn token_spans() {
+        // Arrange
+        let input = "let ten =\n  10;";
+        let cases = vec![
+            (Kind::Let, "let", Span::new(0, 3)),
+            (Kind::Ident, "ten", Span::new(4, 7)),
+            (Kind::Assign, "=", Span::new(8, 9)),
+            (Kind::Int, "10", Span::new(12, 14)),
+            (Kind::SemiColon, ";", Span::new(14, 15)),
+            (Kind::Eof, "", Span::new(15, 15)),
+        ];
+
+        // Act
+        let mut lexer = Lexer::new(input);
+
+        // Assert
+        for case in cases {
+            let token = lexer.next_token();
+            assert_eq!(token.kind, case.0);
+            assert_eq!(token.literal, case.1);
+            assert_eq!(token.span, case.2);
+            assert_eq!(&input[token.span.start..token.span.end], token.literal);
+        }
+    }
+
+    #[test]
+    fn iterator_stops_at_eof() {
+        // Arrange
+        let input = "let x = @;";
+
+        // Act
+        let tokens = tokenize(input);
+
+        // Assert
+        let kinds = tokens.iter().map(|t| t.kind).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                Kind::Let,
+                Kind::Ident,
+                Kind::Assign,
+                Kind::Illegal(Reason::UnexpectedChar('@')),
+                Kind::SemiColon
+            ]
+        );
+        assert_eq!(Lexer::new("").next(), None);
+    }
+
+    #[test]
+    fn token_positions() {
+        // Arrange
+        let input = "let x = 5;\n  x @ 1";
+        let cases = vec![
+            (Kind::Let, Position::new(1, 1)),
+            (Kind::Ident, Position::new(1, 5)),
+            (Kind::Assign, Position::new(1, 7)),
+            (Kind::Int, Position::new(1, 9)),
+            (Kind::SemiColon, Position::new(1, 10)),
+            (Kind::Ident, Position::new(2, 3)),
+            (
+                Kind::Illegal(Reason::UnexpectedChar('@')),
+                Position::new(2, 5),
+            ),
+            (Kind::Int, Position::new(2, 7)),
+            (Kind::Eof, Position::new(2, 8)),
+        ];
+
+        // Act
+        let mut lexer = Lexer::new(input);
+
+        // Assert
+        for case in cases {
+            let token = lexer.next_token();
+            assert_eq!(token.kind, case.0);
+            assert_eq!(token.position, case.1);
+        }
+    }
+
+    #[test]
+    fn strings() {
+        // Arrange
+        let input = r#""foobar" "foo bar" "a\"b\n" """#;
+        let cases = vec![
+            (Kind::Str, "foobar", "foobar"),
+            (Kind::Str, "foo bar", "foo bar"),
+            (Kind::Str, r#"a\"b\n"#, "a\"b\n"),
+            (Kind::Str, "", ""),
+        ];
+
+        // Act
+        let mut lexer = Lexer::new(input);
+
+        // Assert
+        for case in cases {
+            let token = lexer.next_token();
+            assert_eq!(token.kind, case.0);
+            assert_eq!(token.literal, case.1);
+            assert_eq!(unescape(token.literal), case.2);
+        }
+        assert_eq!(lexer.next_token().kind, Kind::Eof);
+    }
+
+    #[test]
+    fn illegal_tokens() {
+        // Arrange
+        let cases = vec![
+            ("#", Reason::UnexpectedChar('#'), "#"),
+            (r#""abc"#, Reason::UnterminatedString, r#""abc"#),
+            (r#""abc\"#, Reason::UnterminatedString, r#""abc\"#),
+            (r#""a\qb""#, Reason::InvalidEscape('q'), r#""a\qb""#),
+            (
+                "99999999999999999999",
+                Reason::IntegerOverflow,
+                "99999999999999999999",
+            ),
+        ];
+
+        for (input, reason, literal) in cases {
+            // Act
+            let mut lexer = Lexer::new(input);
+            let token = lexer.next_token();
+
+            // Assert
+            assert_eq!(token.kind, Kind::Illegal(reason));
+            assert_eq!(token.literal, literal);
+            assert_eq!(lexer.next_token().kind, Kind::Eof);
+        }
+    }
+
+    #[test]
+    fn comparison_and_logical_operators() {
+        // Arrange
+        let input = "a <= b >= c && d || e % f & |";
+        let cases = vec![
+            (Kind::Ident, "a"),
+            (Kind::LtEq, "<="),
+            (Kind::Ident, "b"),
+            (Kind::GtEq, ">="),
+            (Kind::Ident, "c"),
+            (Kind::And, "&&"),
+            (Kind::Ident, "d"),
+            (Kind::Or, "||"),
+            (Kind::Ident, "e"),
+            (Kind::Percent, "%"),
+            (Kind::Ident, "f"),
+            (Kind::Illegal(Reason::UnexpectedChar('&')), "&"),
+            (Kind::Illegal(Reason::UnexpectedChar('|')), "|"),
+            (Kind::Eof, ""),
+        ];
+
+        // Act
+        let mut lexer = Lexer::new(input);
+
+        // Assert
+        for case in cases {
+            let token = lexer.next_token();
+            assert_eq!(token.kind, case.0);
+            assert_eq!(token.literal, case.1);
+        }
+    }
+
+    #[test]
+    fn integer_literals() {
+        // Arrange
+        let cases = vec![
+            ("0", 0),
+            ("1_000_000", 1_000_000),
+            ("0xff", 255),
+            ("0xDead_Beef", 0xdead_beef),
+            ("0o755", 0o755),
+            ("0b1010_1010", 0b1010_1010),
+            ("9223372036854775807", i64::MAX),
+            ("0x7fff_ffff_ffff_ffff", i64::MAX),
+        ];
+
+        for (input, value) in cases {
+            // Act
+            let mut lexer = Lexer::new(input);
+            let token = lexer.next_token();
+
+            // Assert
+            assert_eq!(token.kind, Kind::Int, "{input}");
+            assert_eq!(token.literal, input);
+            assert_eq!(parse_integer(token.literal), Ok(value));
+            assert_eq!(lexer.next_token().kind, Kind::Eof);
+        }
+    }
+
+    #[test]
+    fn negative_integer_literals() {
+        // Arrange
+        let cases = vec![
+            ("1_000", Ok(-1000)),
+            ("9223372036854775808", Ok(i64::MIN)),
+            ("0x8000_0000_0000_0000", Ok(i64::MIN)),
+            ("9223372036854775809", Err(())),
+        ];
+
+        for (literal, expected) in cases {
+            // Act
+            let value = parse_negative_integer(literal);
+
+            // Assert
+            assert_eq!(value.map_err(|_| ()), expected, "{literal}");
+        }
+    }
+
+    #[test]
+    fn malformed_integer_literals() {
+        // Arrange
+        let cases = vec![
+            ("9223372036854775808", Reason::IntegerOverflow),
+            ("0x8000_0000_0000_0000", Reason::IntegerOverflow),
+            ("0b102", Reason::InvalidDigit('2')),
+            ("0o8", Reason::InvalidDigit('8')),
+            ("12ab", Reason::InvalidDigit('a')),
+            ("0x", Reason::MissingDigits),
+            ("1e", Reason::MissingDigits),
+            ("1.5e+", Reason::MissingDigits),
+            ("1.5x", Reason::InvalidDigit('x')),
+            ("0b__", Reason::MissingDigits),
+            ("1_", Reason::MisplacedSeparator),
+            ("1__0", Reason::MisplacedSeparator),
+            ("0x_ff", Reason::MisplacedSeparator),
+            ("0b1_", Reason::MisplacedSeparator),
+            ("1_.5", Reason::MisplacedSeparator),
+            ("1.5_", Reason::MisplacedSeparator),
+            ("1e_5", Reason::MisplacedSeparator),
+            ("1e5_", Reason::MisplacedSeparator),
+        ];
+
+        for (input, reason) in cases {
+            // Act
+            let mut lexer = Lexer::new(input);
+            let token = lexer.next_token();
+
+            // Assert
+            assert_eq!(token.kind, Kind::Illegal(reason), "{input}");
+            assert_eq!(token.literal, input);
+            assert_eq!(lexer.next_token().kind, Kind::Eof);
+        }
+    }
+
+    #[test]
+    fn float_literals() {
+        // Arrange
+        let cases = vec![
+            ("2.75", 2.75),
+            ("0.5", 0.5),
+            ("1e-9", 1e-9),
+            ("2.5E3", 2500.0),
+            ("6.022e+23", 6.022e23),
+            ("1_000.000_1", 1000.0001),
+        ];
+
+        for (input, value) in cases {
+            // Act
+            let mut lexer = Lexer::new(input);
+            let token = lexer.next_token();
+
+            // Assert
+            assert_eq!(token.kind, Kind::Float, "{input}");
+            assert_eq!(token.literal, input);
+            assert_eq!(parse_float(token.literal), Ok(value));
+            assert_eq!(lexer.next_token().kind, Kind::Eof);
+        }
+    }
+
+    #[test]
+    fn dot_after_integer_is_not_a_fraction() {
+        // Arrange
+        let input = "1.";
+
+        // Act
+        let tokens = tokenize(input);
+
+        // Assert
+        assert_eq!(tokens[0].kind, Kind::Int);
+        assert_eq!(tokens[0].literal, "1");
+        assert_eq!(tokens[1].kind, Kind::Dot);
+    }
+
+    #[test]
+    fn assignment_operators() {
+        // Arrange
+        let input = "let mut x = 1; x += 2; x -= 3; x *= 4; x /= 5; x == x + -1";
+        let cases = vec![
+            (Kind::Let, "let"),
+            (Kind::Mut, "mut"),
+            (Kind::Ident, "x"),
+            (Kind::Assign, "="),
+            (Kind::Int, "1"),
+            (Kind::SemiColon, ";"),
+            (Kind::Ident, "x"),
+            (Kind::PlusAssign, "+="),
+            (Kind::Int, "2"),
+            (Kind::SemiColon, ";"),
+            (Kind::Ident, "x"),
+            (Kind::MinusAssign, "-="),
+            (Kind::Int, "3"),
+            (Kind::SemiColon, ";"),
+            (Kind::Ident, "x"),
+            (Kind::AsterixAssign, "*="),
+            (Kind::Int, "4"),
+            (Kind::SemiColon, ";"),
+            (Kind::Ident, "x"),
+            (Kind::SlashAssign, "/="),
+            (Kind::Int, "5"),
+            (Kind::SemiColon, ";"),
+            (Kind::Ident, "x"),
+            (Kind::Eq, "=="),
+            (Kind::Ident, "x"),
+            (Kind::Plus, "+"),
+            (Kind::Minus, "-"),
+            (Kind::Int, "1"),
+            (Kind::Eof, ""),
+        ];
+
+        // Act
+        let mut lexer = Lexer::new(input);
+
+        // Assert
+        for case in cases {
+            let token = lexer.next_token();
+            assert_eq!(token.kind, case.0);
+            assert_eq!(token.literal, case.1);
+        }
+    }
+
+    #[test]
+    fn for_loops_and_ranges() {
+        // Arrange
+        let input = r#"for (x in 0..=n) {} 1..2.5"#;
+        let cases = vec![
+            (Kind::For, "for"),
+            (Kind::LParen, "("),
+            (Kind::Ident, "x"),
+            (Kind::In, "in"),
+            (Kind::Int, "0"),
+            (Kind::DotDotEq, "..="),
+            (Kind::Ident, "n"),
+            (Kind::RParen, ")"),
+            (Kind::LBrace, "{"),
+            (Kind::RBrace, "}"),
+            (Kind::Int, "1"),
+            (Kind::DotDot, ".."),
+            (Kind::Float, "2.5"),
+            (Kind::Eof, ""),
+        ];
+
+        // Act
+        let mut lexer = Lexer::new(input);
+
+        // Assert
+        for case in cases {
+            let token = lexer.next_token();
+            assert_eq!(token.kind, case.0);
+            assert_eq!(token.literal, case.1);
+        }
+    }
+
+    #[test]
+    fn default_and_rest_parameters() {
+        // Arrange
+        let input = r#"fn(a, b = 1, ...rest) {} f(...xs)"#;
+        let cases = vec![
+            (Kind::Function, "fn"),
+            (Kind::LParen, "("),
+            (Kind::Ident, "a"),
+            (Kind::Comma, ","),
+            (Kind::Ident, "b"),
+            (Kind::Assign, "="),
+            (Kind::Int, "1"),
+            (Kind::Comma, ","),
+            (Kind::DotDotDot, "..."),
+            (Kind::Ident, "rest"),
+            (Kind::RParen, ")"),
+            (Kind::LBrace, "{"),
+            (Kind::RBrace, "}"),
+            (Kind::Ident, "f"),
+            (Kind::LParen, "("),
+            (Kind::DotDotDot, "..."),
+            (Kind::Ident, "xs"),
+            (Kind::RParen, ")"),
+            (Kind::Eof, ""),
+        ];
+
+        // Act
+        let mut lexer = Lexer::new(input);
+
+        // Assert
+        for case in cases {
+            let token = lexer.next_token();
+            assert_eq!(token.kind, case.0);
+            assert_eq!(token.literal, case.1);
+        }
+    }
+
+    #[test]
+    fn conditional_expressions() {
+        // Arrange
+        let input = r#"if (a) {} else if (b) {} a ? b : c"#;
+        let cases = vec![
+            (Kind::If, "if"),
+            (Kind::LParen, "("),
+            (Kind::Ident, "a"),
+            (Kind::RParen, ")"),
+            (Kind::LBrace, "{"),
+            (Kind::RBrace, "}"),
+            (Kind::Else, "else"),
+            (Kind::If, "if"),
+            (Kind::LParen, "("),
+            (Kind::Ident, "b"),
+            (Kind::RParen, ")"),
+            (Kind::LBrace, "{"),
+            (Kind::RBrace, "}"),
+            (Kind::Ident, "a"),
+            (Kind::Question, "?"),
+            (Kind::Ident, "b"),
+            (Kind::Colon, ":"),
+            (Kind::Ident, "c"),
+            (Kind::Eof, ""),
+        ];
+
+        // Act
+        let mut lexer = Lexer::new(input);
+
+        // Assert
+        for case in cases {
+            let token = lexer.next_token();
+            assert_eq!(token.kind, case.0);
+            assert_eq!(token.literal, case.1);
+        }
+    }
+
+    #[test]
+    fn match_expressions() {
+        // Arrange
+        let input = r#"match (x) { [_, 1] => 1, {"a": y} => y }"#;
+        let cases = vec![
+            (Kind::Match, "match"),
+            (Kind::LParen, "("),
+            (Kind::Ident, "x"),
+            (Kind::RParen, ")"),
+            (Kind::LBrace, "{"),
+            (Kind::LBracket, "["),
+            (Kind::Ident, "_"),
+            (Kind::Comma, ","),
+            (Kind::Int, "1"),
+            (Kind::RBracket, "]"),
+            (Kind::FatArrow, "=>"),
+            (Kind::Int, "1"),
+            (Kind::Comma, ","),
+            (Kind::LBrace, "{"),
+            (Kind::Str, "a"),
+            (Kind::Colon, ":"),
+            (Kind::Ident, "y"),
+            (Kind::RBrace, "}"),
+            (Kind::FatArrow, "=>"),
+            (Kind::Ident, "y"),
+            (Kind::RBrace, "}"),
+            (Kind::Eof, ""),
+        ];
+
+        // Act
+        let mut lexer = Lexer::new(input);
+
+        // Assert
+        for case in cases {
+            let token = lexer.next_token();
+            assert_eq!(token.kind, case.0);
+            assert_eq!(token.literal, case.1);
+        }
+    }
+
+    #[test]
+    fn modules() {
+        // Arrange
+        let input = r#"import "m.gb" as m; export let y = m.x;"#;
+        let cases = vec![
+            (Kind::Import, "import"),
+            (Kind::Str, "m.gb"),
+            (Kind::As, "as"),
+            (Kind::Ident, "m"),
+            (Kind::SemiColon, ";"),
+            (Kind::Export, "export"),
+            (Kind::Let, "let"),
+            (Kind::Ident, "y"),
+            (Kind::Assign, "="),
+            (Kind::Ident, "m"),
+            (Kind::Dot, "."),
+            (Kind::Ident, "x"),
+            (Kind::SemiColon, ";"),
+            (Kind::Eof, ""),
+        ];
+
         // Act
         let mut lexer = Lexer::new(input);
 
@@ -1,5 +1,10 @@
-mod lexer;
-mod object;
+pub mod evaluator;
+pub mod interpreter;
+pub mod lexer;
+pub mod object;
 pub mod parser;
 pub mod repl;
-mod token;
+pub mod token;
+
+pub use evaluator::Error;
+pub use interpreter::{Interpreter, Value, ValueKind};
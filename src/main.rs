@@ -1,12 +1,160 @@
-use std::io;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
-use gibbon::repl;
+use gibbon::evaluator::{Capabilities, PathAccess};
+use gibbon::lexer::Lexer;
+use gibbon::parser::ast::json;
+use gibbon::parser::Parser;
+use gibbon::{repl, Interpreter};
+
+static USAGE: &str = "usage: gibbon [--allow-all] [--allow-read[=PATHS]] [--allow-write[=PATHS]] [--allow-clock] [--allow-env] [--allow-import[=PATHS]] [FILE]
+       gibbon ast [--json] [FILE]";
+
+fn main() -> ExitCode {
+    // Scripts run from the command line can always print.
+    let mut capabilities = Capabilities {
+        stdout: true,
+        ..Capabilities::default()
+    };
+    let mut file = None;
+    let mut arguments = std::env::args().skip(1).peekable();
+
+    if arguments.next_if(|argument| argument == "ast").is_some() {
+        return print_ast(arguments);
+    }
+
+    for argument in arguments {
+        let (flag, value) = match argument.split_once('=') {
+            Some((flag, value)) => (flag, Some(value)),
+            None => (argument.as_str(), None),
+        };
+
+        match (flag, value) {
+            ("--allow-all", None) => capabilities = Capabilities::all(),
+            ("--allow-read", value) => capabilities.read = path_access(value),
+            ("--allow-write", value) => capabilities.write = path_access(value),
+            ("--allow-clock", None) => capabilities.clock = true,
+            ("--allow-env", None) => capabilities.env = true,
+            ("--allow-import", value) => capabilities.import = path_access(value),
+            _ if !argument.starts_with('-') && file.is_none() => file = Some(argument),
+            _ => {
+                eprintln!("unknown argument: {argument}");
+                eprintln!("{USAGE}");
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    // Without a flag, scripts can import modules next to them.
+    if capabilities.import == PathAccess::None {
+        let directory = match &file {
+            Some(path) => Path::new(path).parent().map(Path::to_path_buf),
+            None => std::env::current_dir().ok(),
+        };
+        capabilities.import = PathAccess::Only(directory.into_iter().collect());
+    }
+
+    if let Some(path) = file {
+        return run_file(&path, capabilities);
+    }
 
-fn main() {
     println!("This is Gibbon!");
     println!("Begin typing commands.");
 
     let mut stdin = io::stdin();
 
-    repl::start(&mut stdin);
+    repl::start(&mut stdin, capabilities);
+
+    ExitCode::SUCCESS
+}
+
+/// `--allow-read` allows every path, and `--allow-read=a,b` only those.
+fn path_access(paths: Option<&str>) -> PathAccess {
+    match paths {
+        None => PathAccess::All,
+        Some(paths) => PathAccess::Only(paths.split(',').map(PathBuf::from).collect()),
+    }
+}
+
+/// Parses FILE, or standard input without one, and prints the syntax tree
+/// as source text or, with `--json`, as JSON.
+fn print_ast(arguments: impl Iterator<Item = String>) -> ExitCode {
+    let mut as_json = false;
+    let mut file = None;
+
+    for argument in arguments {
+        match argument.as_str() {
+            "--json" => as_json = true,
+            _ if !argument.starts_with('-') && file.is_none() => file = Some(argument),
+            _ => {
+                eprintln!("unknown argument: {argument}");
+                eprintln!("{USAGE}");
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    let source = match &file {
+        Some(path) => fs::read_to_string(path),
+        None => {
+            let mut source = String::new();
+            io::stdin().read_to_string(&mut source).map(|_| source)
+        }
+    };
+    let source = match source {
+        Ok(source) => source,
+        Err(error) => {
+            eprintln!(
+                "ERROR: cannot read {}: {error}",
+                file.as_deref().unwrap_or("stdin")
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut parser = Parser::new(Lexer::new(&source));
+    let program = parser.parse_program();
+    for warning in &parser.warnings {
+        eprintln!("warning: {warning}");
+    }
+    if !parser.errors.is_empty() {
+        for error in &parser.errors {
+            eprintln!("ERROR: {error}");
+        }
+        return ExitCode::FAILURE;
+    }
+
+    if as_json {
+        println!("{}", json::to_json(&program));
+    } else {
+        println!("{program}");
+    }
+    ExitCode::SUCCESS
+}
+
+fn run_file(path: &str, capabilities: Capabilities) -> ExitCode {
+    let mut interpreter = Interpreter::new();
+    interpreter.set_capabilities(capabilities);
+
+    let result = interpreter.eval_file(path);
+    for warning in interpreter.warnings() {
+        eprintln!("warning: {warning}");
+    }
+
+    match result {
+        Ok(value) if value.is_null() => ExitCode::SUCCESS,
+        Ok(value) => {
+            println!("{value}");
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            if !error.stack.is_empty() {
+                eprintln!("{}", error.traceback());
+            }
+            eprintln!("ERROR: {error}");
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,52 @@
+use std::rc::Rc;
+
+use super::{drop_nested, Object, ObjectType, ValueKind, STACK_RED_ZONE, STACK_SEGMENT};
+
+/// The elements are shared, so copying an array is cheap. Writes go
+/// through `Rc::make_mut`, which only copies when the array is shared.
+#[derive(Clone, Debug)]
+pub struct Array(pub Rc<Vec<Object>>);
+
+impl Array {
+    /// The elements, copied only if the array is shared.
+    pub fn into_elements(mut self) -> Vec<Object> {
+        Rc::unwrap_or_clone(std::mem::take(&mut self.0))
+    }
+}
+
+impl ObjectType for Array {
+    fn kind(&self) -> ValueKind {
+        ValueKind::Array
+    }
+
+    fn inspect(&self) -> String {
+        let elements = self
+            .0
+            .iter()
+            .map(|e| stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT, || e.inspect()))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!("[{elements}]")
+    }
+}
+
+impl Drop for Array {
+    fn drop(&mut self) {
+        if let Some(elements) = Rc::get_mut(&mut self.0) {
+            drop_nested(std::mem::take(elements));
+        }
+    }
+}
+
+impl From<Vec<Object>> for Array {
+    fn from(elements: Vec<Object>) -> Self {
+        Array(Rc::new(elements))
+    }
+}
+
+impl FromIterator<Object> for Array {
+    fn from_iter<I: IntoIterator<Item = Object>>(elements: I) -> Self {
+        Array(Rc::new(elements.into_iter().collect()))
+    }
+}
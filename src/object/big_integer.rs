@@ -0,0 +1,28 @@
+use num_bigint::BigInt;
+
+use super::{Integer, Object, ObjectType, ValueKind};
+
+/// An integer outside the range of `i64`, only produced when the evaluator
+/// promotes on overflow. To scripts it is just another integer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BigInteger(pub BigInt);
+
+impl BigInteger {
+    /// Wraps `value`, demoting it back to an `Integer` when it fits.
+    pub fn normalize(value: BigInt) -> Object {
+        match i64::try_from(&value) {
+            Ok(value) => Object::Integer(Integer(value)),
+            Err(_) => Object::BigInteger(BigInteger(value)),
+        }
+    }
+}
+
+impl ObjectType for BigInteger {
+    fn kind(&self) -> ValueKind {
+        ValueKind::Integer
+    }
+
+    fn inspect(&self) -> String {
+        format!("{}", self.0)
+    }
+}
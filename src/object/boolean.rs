@@ -1,10 +1,11 @@
-use super::{ObjectKind, ObjectType};
+use super::{ObjectType, ValueKind};
 
-pub struct Boolean(bool);
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Boolean(pub bool);
 
 impl ObjectType for Boolean {
-    fn kind(&self) -> ObjectKind {
-        return ObjectKind::BOOLEAN;
+    fn kind(&self) -> ValueKind {
+        ValueKind::Boolean
     }
 
     fn inspect(&self) -> String {
@@ -0,0 +1,115 @@
+use std::fmt::Debug;
+use std::rc::Rc;
+
+use crate::evaluator::Error;
+
+use super::{Object, ObjectType, Value, ValueKind};
+
+/// The signature of a function implemented in Rust.
+pub type NativeFunction = dyn Fn(&[Object]) -> Result<Object, Error>;
+
+/// A function implemented in Rust that scripts call like any other.
+#[derive(Clone)]
+pub struct Builtin {
+    pub name: String,
+    pub function: Rc<NativeFunction>,
+}
+
+impl Builtin {
+    pub fn new(
+        name: impl Into<String>,
+        function: impl Fn(&[Object]) -> Result<Object, Error> + 'static,
+    ) -> Self {
+        Builtin {
+            name: name.into(),
+            function: Rc::new(function),
+        }
+    }
+
+    /// Wraps a closure with typed parameters. Arguments are converted with
+    /// `TryFrom<Value>`, and a wrong number of arguments or an argument of
+    /// the wrong type is an error naming the function.
+    pub fn typed<Args, F: TypedFunction<Args> + 'static>(
+        name: impl Into<String>,
+        function: F,
+    ) -> Self {
+        let name = name.into();
+        Builtin {
+            name: name.clone(),
+            function: Rc::new(move |arguments: &[Object]| {
+                let arguments = arguments.iter().cloned().map(Value).collect::<Vec<_>>();
+                function.call(&name, &arguments).map(|value| value.0)
+            }),
+        }
+    }
+
+    pub fn call(&self, arguments: &[Object]) -> Result<Object, Error> {
+        (self.function)(arguments)
+    }
+}
+
+impl ObjectType for Builtin {
+    fn kind(&self) -> ValueKind {
+        ValueKind::Builtin
+    }
+
+    fn inspect(&self) -> String {
+        format!("builtin {}", self.name)
+    }
+}
+
+impl Debug for Builtin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Builtin")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A Rust closure whose parameters convert from values and whose result
+/// converts into one. Implemented for closures of up to four parameters.
+pub trait TypedFunction<Args> {
+    fn call(&self, name: &str, arguments: &[Value]) -> Result<Value, Error>;
+}
+
+macro_rules! typed_function {
+    ($count:literal $(, $arg:ident $index:tt)*) => {
+        impl<F, R, $($arg),*> TypedFunction<($($arg,)*)> for F
+        where
+            F: Fn($($arg),*) -> Result<R, Error>,
+            R: Into<Value>,
+            $($arg: TryFrom<Value>,)*
+            $(Error: From<<$arg as TryFrom<Value>>::Error>,)*
+        {
+            #[allow(unused_variables)]
+            fn call(&self, name: &str, arguments: &[Value]) -> Result<Value, Error> {
+                if arguments.len() != $count {
+                    return Err(Error::new(format!(
+                        "wrong number of arguments to {name}: expected {}, got {}",
+                        $count,
+                        arguments.len()
+                    )));
+                }
+
+                self($(argument::<$arg>(name, arguments, $index)?),*).map(Into::into)
+            }
+        }
+    };
+}
+
+typed_function!(0);
+typed_function!(1, A 0);
+typed_function!(2, A 0, B 1);
+typed_function!(3, A 0, B 1, C 2);
+typed_function!(4, A 0, B 1, C 2, D 3);
+
+fn argument<T>(name: &str, arguments: &[Value], index: usize) -> Result<T, Error>
+where
+    T: TryFrom<Value>,
+    Error: From<T::Error>,
+{
+    T::try_from(arguments[index].clone()).map_err(|error| {
+        let error = Error::from(error);
+        Error::new(format!("argument {} to {name}: {error}", index + 1))
+    })
+}
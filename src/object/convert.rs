@@ -0,0 +1,236 @@
+//! Conversions between Rust values and gibbon values, for host code
+//! embedding the interpreter.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+
+use super::{
+    Array, Boolean, Float, Hash, HashKey, Integer, Null, Object, ObjectType, Str, Value, ValueKind,
+};
+use crate::evaluator::Error;
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value(Object::Integer(Integer(value)))
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value(Object::Float(Float(value)))
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value(Object::Boolean(Boolean(value)))
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value(Object::Str(Str(value)))
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value(Object::Str(Str(value.to_string())))
+    }
+}
+
+/// The unit value converts to null, for functions run for their effect.
+impl From<()> for Value {
+    fn from((): ()) -> Self {
+        Value(Object::Null(Null))
+    }
+}
+
+/// `None` converts to null.
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value(Object::Null(Null)), Into::into)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(values: Vec<T>) -> Self {
+        let elements = values.into_iter().map(|value| value.into().0);
+        Value(Object::Array(elements.collect::<Array>()))
+    }
+}
+
+impl<V: Into<Value>> From<HashMap<String, V>> for Value {
+    fn from(pairs: HashMap<String, V>) -> Self {
+        let pairs = pairs
+            .into_iter()
+            .map(|(key, value)| (HashKey::Str(key), value.into().0));
+        Value(Object::Hash(pairs.collect::<Hash>()))
+    }
+}
+
+/// Lets a `Value` stand for any value where a conversion is expected, as
+/// in `Vec<Value>` or a typed function parameter.
+impl From<Infallible> for Error {
+    fn from(never: Infallible) -> Self {
+        match never {}
+    }
+}
+
+impl TryFrom<Value> for i64 {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Error> {
+        match value.0 {
+            Object::Integer(Integer(value)) => Ok(value),
+            Object::BigInteger(value) => Err(Error::new(format!(
+                "integer {} does not fit in 64 bits",
+                value.inspect()
+            ))),
+            value => Err(mismatch(ValueKind::Integer, &value)),
+        }
+    }
+}
+
+/// Integers are accepted too, as they are wherever a float is expected.
+impl TryFrom<Value> for f64 {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Error> {
+        match value.0 {
+            Object::Float(Float(value)) => Ok(value),
+            Object::Integer(Integer(value)) => Ok(value as f64),
+            value => Err(mismatch(ValueKind::Float, &value)),
+        }
+    }
+}
+
+impl TryFrom<Value> for bool {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Error> {
+        match value.0 {
+            Object::Boolean(Boolean(value)) => Ok(value),
+            value => Err(mismatch(ValueKind::Boolean, &value)),
+        }
+    }
+}
+
+impl TryFrom<Value> for String {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Error> {
+        match value.0 {
+            Object::Str(Str(value)) => Ok(value),
+            value => Err(mismatch(ValueKind::String, &value)),
+        }
+    }
+}
+
+impl<T> TryFrom<Value> for Vec<T>
+where
+    T: TryFrom<Value>,
+    Error: From<T::Error>,
+{
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Error> {
+        match value.0 {
+            Object::Array(array) => array
+                .into_elements()
+                .into_iter()
+                .map(|element| Ok(T::try_from(Value(element))?))
+                .collect(),
+            value => Err(mismatch(ValueKind::Array, &value)),
+        }
+    }
+}
+
+/// Only hashes whose keys are all strings convert.
+impl<V> TryFrom<Value> for HashMap<String, V>
+where
+    V: TryFrom<Value>,
+    Error: From<V::Error>,
+{
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Error> {
+        let Object::Hash(hash) = value.0 else {
+            return Err(mismatch(ValueKind::Hash, &value.0));
+        };
+
+        hash.into_pairs()
+            .into_iter()
+            .map(|(key, value)| match key {
+                HashKey::Str(key) => Ok((key, V::try_from(Value(value))?)),
+                key => Err(mismatch(ValueKind::String, &key.to_object())),
+            })
+            .collect()
+    }
+}
+
+fn mismatch(expected: ValueKind, value: &Object) -> Error {
+    Error::new(format!("expected {expected}, got {}", value.kind()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::super::Value;
+
+    #[test]
+    fn rust_values_convert_to_values() {
+        // Arrange
+        let scores = HashMap::from([("bo".to_string(), vec![1_i64, 2])]);
+        let tests = vec![
+            (Value::from(42_i64), "42"),
+            (Value::from(1.5), "1.5"),
+            (Value::from(true), "true"),
+            (Value::from("hi"), "hi"),
+            (Value::from(()), "null"),
+            (Value::from(Some(1_i64)), "1"),
+            (Value::from(None::<i64>), "null"),
+            (Value::from(vec!["a", "b"]), "[a, b]"),
+            (
+                Value::from(vec![Value::from(1_i64), Value::from("b")]),
+                "[1, b]",
+            ),
+            (Value::from(scores), "{bo: [1, 2]}"),
+        ];
+
+        for (value, expected) in tests {
+            // Act
+            let displayed = value.to_string();
+
+            // Assert
+            assert_eq!(displayed, expected);
+        }
+    }
+
+    #[test]
+    fn values_convert_to_rust_values() {
+        // Arrange
+        let array = Value::from(vec![vec![1_i64], vec![2, 3]]);
+        let hash = Value::from(HashMap::from([("on".to_string(), true)]));
+
+        // Act
+        let nested = Vec::<Vec<i64>>::try_from(array.clone());
+        let values = Vec::<Value>::try_from(array.clone());
+        let flags = HashMap::<String, bool>::try_from(hash);
+        let wrong = Vec::<String>::try_from(array);
+        let float = f64::try_from(Value::from(2_i64));
+
+        // Assert
+        assert_eq!(nested.unwrap(), vec![vec![1], vec![2, 3]]);
+        let values = values
+            .unwrap()
+            .iter()
+            .map(Value::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(values, ["[1]", "[2, 3]"]);
+        assert_eq!(flags.unwrap(), HashMap::from([("on".to_string(), true)]));
+        assert_eq!(wrong.unwrap_err().message, "expected STRING, got ARRAY");
+        assert_eq!(float.unwrap(), 2.0);
+    }
+}
@@ -0,0 +1,83 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use super::{Null, Object};
+
+pub type Env = Rc<RefCell<Environment>>;
+
+/// Why an assignment could not be made.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AssignError {
+    Undeclared,
+    Immutable,
+}
+
+#[derive(Default)]
+pub struct Environment {
+    store: HashMap<String, Object>,
+    mutable: HashSet<String>,
+    outer: Option<Env>,
+}
+
+impl Environment {
+    pub fn new() -> Env {
+        Rc::new(RefCell::new(Environment::default()))
+    }
+
+    pub fn enclosed(outer: &Env) -> Env {
+        Rc::new(RefCell::new(Environment {
+            outer: Some(Rc::clone(outer)),
+            ..Environment::default()
+        }))
+    }
+
+    pub fn get(&self, name: &str) -> Option<Object> {
+        match self.store.get(name) {
+            Some(value) => Some(value.clone()),
+            None => self.outer.as_ref()?.borrow().get(name),
+        }
+    }
+
+    /// Declares an immutable binding, replacing any earlier one of the same
+    /// name in this scope.
+    pub fn set(&mut self, name: String, value: Object) {
+        self.mutable.remove(&name);
+        self.store.insert(name, value);
+    }
+
+    /// Declares a binding that can later be assigned to.
+    pub fn set_mutable(&mut self, name: String, value: Object) {
+        self.mutable.insert(name.clone());
+        self.store.insert(name, value);
+    }
+
+    /// Rebinds an existing name in the innermost scope that declares it.
+    pub fn assign(&mut self, name: &str, value: Object) -> Result<(), AssignError> {
+        match self.store.get_mut(name) {
+            Some(_) if !self.mutable.contains(name) => Err(AssignError::Immutable),
+            Some(slot) => {
+                *slot = value;
+                Ok(())
+            }
+            None => match &self.outer {
+                Some(outer) => outer.borrow_mut().assign(name, value),
+                None => Err(AssignError::Undeclared),
+            },
+        }
+    }
+
+    /// Moves the value out of an assignable binding, leaving null behind
+    /// until it is assigned back. Updating the taken value does not copy
+    /// collections that were only shared with the binding.
+    pub fn take(&mut self, name: &str) -> Result<Object, AssignError> {
+        match self.store.get_mut(name) {
+            Some(_) if !self.mutable.contains(name) => Err(AssignError::Immutable),
+            Some(slot) => Ok(std::mem::replace(slot, Object::Null(Null))),
+            None => match &self.outer {
+                Some(outer) => outer.borrow_mut().take(name),
+                None => Err(AssignError::Undeclared),
+            },
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use super::{ObjectType, ValueKind};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Float(pub f64);
+
+impl ObjectType for Float {
+    fn kind(&self) -> ValueKind {
+        ValueKind::Float
+    }
+
+    /// Whole numbers keep a trailing `.0` so floats can be told apart from
+    /// integers, and the special values print as `NaN`, `Infinity` and
+    /// `-Infinity`.
+    fn inspect(&self) -> String {
+        match self.0 {
+            value if value.is_nan() => "NaN".to_string(),
+            f64::INFINITY => "Infinity".to_string(),
+            f64::NEG_INFINITY => "-Infinity".to_string(),
+            value => format!("{value:?}"),
+        }
+    }
+}
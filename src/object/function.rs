@@ -0,0 +1,56 @@
+use std::fmt::Debug;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crate::parser::ast::{BlockStatement, Parameter};
+
+use super::{Env, ObjectType, ValueKind};
+
+#[derive(Clone)]
+pub struct Function {
+    pub name: Option<String>,
+    pub parameters: Vec<Parameter>,
+    pub body: BlockStatement,
+    pub env: Env,
+    /// The file the function was defined in, if any.
+    pub file: Option<Rc<PathBuf>>,
+}
+
+impl Function {
+    /// The name to show for this function in messages.
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or("anonymous function")
+    }
+}
+
+impl ObjectType for Function {
+    fn kind(&self) -> ValueKind {
+        ValueKind::Function
+    }
+
+    fn inspect(&self) -> String {
+        let parameters = self
+            .parameters
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        match &self.name {
+            Some(name) => format!("fn {name}({parameters}) {{ {} }}", self.body),
+            None => format!("fn({parameters}) {{ {} }}", self.body),
+        }
+    }
+}
+
+// The environment can hold this function, so it is left out to avoid
+// printing a cycle.
+impl Debug for Function {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Function")
+            .field("name", &self.name)
+            .field("parameters", &self.parameters)
+            .field("body", &self.body)
+            .finish_non_exhaustive()
+    }
+}
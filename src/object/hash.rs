@@ -0,0 +1,82 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::rc::Rc;
+
+use super::{
+    drop_nested, Boolean, Integer, Object, ObjectType, Str, ValueKind, STACK_RED_ZONE,
+    STACK_SEGMENT,
+};
+
+/// The objects that can be used as hash keys. Keys are ordered, which is
+/// also the order a hash iterates in.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum HashKey {
+    Integer(i64),
+    Boolean(bool),
+    Str(String),
+}
+
+impl HashKey {
+    pub fn to_object(&self) -> Object {
+        match self {
+            HashKey::Integer(value) => Object::Integer(Integer(*value)),
+            HashKey::Boolean(value) => Object::Boolean(Boolean(*value)),
+            HashKey::Str(value) => Object::Str(Str(value.clone())),
+        }
+    }
+}
+
+impl Display for HashKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HashKey::Integer(value) => write!(f, "{value}"),
+            HashKey::Boolean(value) => write!(f, "{value}"),
+            HashKey::Str(value) => write!(f, "{value:?}"),
+        }
+    }
+}
+
+/// Like [`Array`](super::Array), the pairs are shared and copied on write.
+#[derive(Clone, Debug, Default)]
+pub struct Hash(pub Rc<BTreeMap<HashKey, Object>>);
+
+impl Hash {
+    /// The pairs, copied only if the hash is shared.
+    pub fn into_pairs(mut self) -> BTreeMap<HashKey, Object> {
+        Rc::unwrap_or_clone(std::mem::take(&mut self.0))
+    }
+}
+
+impl ObjectType for Hash {
+    fn kind(&self) -> ValueKind {
+        ValueKind::Hash
+    }
+
+    fn inspect(&self) -> String {
+        let pairs = self
+            .0
+            .iter()
+            .map(|(key, value)| {
+                let value = stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT, || value.inspect());
+                format!("{}: {value}", key.to_object().inspect())
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!("{{{pairs}}}")
+    }
+}
+
+impl Drop for Hash {
+    fn drop(&mut self) {
+        if let Some(pairs) = Rc::get_mut(&mut self.0) {
+            drop_nested(std::mem::take(pairs).into_values().collect());
+        }
+    }
+}
+
+impl FromIterator<(HashKey, Object)> for Hash {
+    fn from_iter<I: IntoIterator<Item = (HashKey, Object)>>(pairs: I) -> Self {
+        Hash(Rc::new(pairs.into_iter().collect()))
+    }
+}
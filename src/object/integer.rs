@@ -1,10 +1,11 @@
-use super::{ObjectKind, ObjectType};
+use super::{ObjectType, ValueKind};
 
-pub struct Integer(i64);
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Integer(pub i64);
 
 impl ObjectType for Integer {
-    fn kind(&self) -> ObjectKind {
-        return ObjectKind::INTEGER;
+    fn kind(&self) -> ValueKind {
+        ValueKind::Integer
     }
 
     fn inspect(&self) -> String {
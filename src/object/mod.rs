@@ -1,14 +1,164 @@
+pub mod array;
+pub mod big_integer;
 pub mod boolean;
+pub mod builtin;
+mod convert;
+pub mod environment;
+pub mod float;
+pub mod function;
+pub mod hash;
 pub mod integer;
+pub mod module;
 pub mod null;
+pub mod range;
+pub mod string;
+pub mod value;
 
-pub enum ObjectKind {
-    INTEGER,
-    BOOLEAN,
-    NULL,
+use std::fmt::Display;
+use std::rc::Rc;
+
+pub use array::Array;
+pub use big_integer::BigInteger;
+pub use boolean::Boolean;
+pub use builtin::{Builtin, NativeFunction, TypedFunction};
+pub use environment::{AssignError, Env, Environment};
+pub use float::Float;
+pub use function::Function;
+pub use hash::{Hash, HashKey};
+pub use integer::Integer;
+pub use module::Module;
+pub use null::Null;
+pub use range::Range;
+pub use string::Str;
+pub use value::Value;
+
+/// Printing a collection that starts with less stack than this left moves
+/// to a new segment of `STACK_SEGMENT` bytes.
+const STACK_RED_ZONE: usize = 64 * 1024;
+const STACK_SEGMENT: usize = 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ValueKind {
+    Integer,
+    Float,
+    Boolean,
+    Null,
+    String,
+    Array,
+    Hash,
+    Range,
+    Function,
+    Builtin,
+    Module,
+}
+
+impl Display for ValueKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ValueKind::Integer => "INTEGER",
+            ValueKind::Float => "FLOAT",
+            ValueKind::Boolean => "BOOLEAN",
+            ValueKind::Null => "NULL",
+            ValueKind::String => "STRING",
+            ValueKind::Array => "ARRAY",
+            ValueKind::Hash => "HASH",
+            ValueKind::Range => "RANGE",
+            ValueKind::Function => "FUNCTION",
+            ValueKind::Builtin => "BUILTIN",
+            ValueKind::Module => "MODULE",
+        };
+        write!(f, "{name}")
+    }
 }
 
 pub trait ObjectType {
-    fn kind(&self) -> ObjectKind;
+    fn kind(&self) -> ValueKind;
     fn inspect(&self) -> String;
 }
+
+#[derive(Clone, Debug)]
+pub enum Object {
+    Integer(Integer),
+    BigInteger(BigInteger),
+    Float(Float),
+    Boolean(Boolean),
+    Null(Null),
+    Str(Str),
+    Array(Array),
+    Hash(Hash),
+    Range(Range),
+    Function(Rc<Function>),
+    Builtin(Builtin),
+    Module(Module),
+}
+
+impl Object {
+    pub fn is_truthy(&self) -> bool {
+        !matches!(self, Object::Null(_) | Object::Boolean(Boolean(false)))
+    }
+
+    pub fn hash_key(&self) -> Option<HashKey> {
+        match self {
+            Object::Integer(Integer(value)) => Some(HashKey::Integer(*value)),
+            Object::Boolean(Boolean(value)) => Some(HashKey::Boolean(*value)),
+            Object::Str(Str(value)) => Some(HashKey::Str(value.clone())),
+            _ => None,
+        }
+    }
+}
+
+/// Drops `objects` and the collections only they hold without recursing, as
+/// collections can nest deeper than the stack allows.
+fn drop_nested(mut objects: Vec<Object>) {
+    while let Some(object) = objects.pop() {
+        match object {
+            Object::Array(mut array) => {
+                if let Some(elements) = Rc::get_mut(&mut array.0) {
+                    objects.append(elements);
+                }
+            }
+            Object::Hash(mut hash) => {
+                if let Some(pairs) = Rc::get_mut(&mut hash.0) {
+                    objects.extend(std::mem::take(pairs).into_values());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl ObjectType for Object {
+    fn kind(&self) -> ValueKind {
+        match self {
+            Object::Integer(integer) => integer.kind(),
+            Object::BigInteger(integer) => integer.kind(),
+            Object::Float(float) => float.kind(),
+            Object::Boolean(boolean) => boolean.kind(),
+            Object::Null(null) => null.kind(),
+            Object::Str(string) => string.kind(),
+            Object::Array(array) => array.kind(),
+            Object::Hash(hash) => hash.kind(),
+            Object::Range(range) => range.kind(),
+            Object::Function(function) => function.kind(),
+            Object::Builtin(builtin) => builtin.kind(),
+            Object::Module(module) => module.kind(),
+        }
+    }
+
+    fn inspect(&self) -> String {
+        match self {
+            Object::Integer(integer) => integer.inspect(),
+            Object::BigInteger(integer) => integer.inspect(),
+            Object::Float(float) => float.inspect(),
+            Object::Boolean(boolean) => boolean.inspect(),
+            Object::Null(null) => null.inspect(),
+            Object::Str(string) => string.inspect(),
+            Object::Array(array) => array.inspect(),
+            Object::Hash(hash) => hash.inspect(),
+            Object::Range(range) => range.inspect(),
+            Object::Function(function) => function.inspect(),
+            Object::Builtin(builtin) => builtin.inspect(),
+            Object::Module(module) => module.inspect(),
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use super::{Object, ObjectType, ValueKind};
+
+/// A loaded module: the file it came from and the values it exports.
+#[derive(Clone, Debug)]
+pub struct Module {
+    pub path: PathBuf,
+    pub exports: BTreeMap<String, Object>,
+}
+
+impl ObjectType for Module {
+    fn kind(&self) -> ValueKind {
+        ValueKind::Module
+    }
+
+    fn inspect(&self) -> String {
+        format!("<module {}>", self.path.display())
+    }
+}
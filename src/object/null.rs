@@ -1,13 +1,14 @@
-use super::{ObjectKind, ObjectType};
+use super::{ObjectType, ValueKind};
 
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Null;
 
 impl ObjectType for Null {
-    fn kind(&self) -> ObjectKind {
-        return ObjectKind::NULL;
+    fn kind(&self) -> ValueKind {
+        ValueKind::Null
     }
 
     fn inspect(&self) -> String {
-        format!("null")
+        "null".to_string()
     }
 }
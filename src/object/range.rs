@@ -0,0 +1,31 @@
+use super::{ObjectType, ValueKind};
+
+/// A range of integers, iterated lazily so large ranges cost nothing up
+/// front.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Range {
+    pub start: i64,
+    pub end: i64,
+    pub inclusive: bool,
+}
+
+impl Range {
+    pub fn iter(&self) -> Box<dyn Iterator<Item = i64>> {
+        if self.inclusive {
+            Box::new(self.start..=self.end)
+        } else {
+            Box::new(self.start..self.end)
+        }
+    }
+}
+
+impl ObjectType for Range {
+    fn kind(&self) -> ValueKind {
+        ValueKind::Range
+    }
+
+    fn inspect(&self) -> String {
+        let operator = if self.inclusive { "..=" } else { ".." };
+        format!("{}{operator}{}", self.start, self.end)
+    }
+}
@@ -0,0 +1,14 @@
+use super::{ObjectType, ValueKind};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Str(pub String);
+
+impl ObjectType for Str {
+    fn kind(&self) -> ValueKind {
+        ValueKind::String
+    }
+
+    fn inspect(&self) -> String {
+        self.0.clone()
+    }
+}
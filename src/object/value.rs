@@ -0,0 +1,26 @@
+use std::fmt::{self, Display, Formatter};
+
+use super::{Object, ObjectType, ValueKind};
+
+/// A gibbon value, as host code sees it. Rust values convert into it with
+/// `From`, and back with `TryFrom`. The object it wraps stays private, so
+/// host code does not depend on how the evaluator represents values.
+#[derive(Clone, Debug)]
+pub struct Value(pub(crate) Object);
+
+impl Value {
+    pub fn kind(&self) -> ValueKind {
+        self.0.kind()
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self.0, Object::Null(_))
+    }
+}
+
+/// Formats the value as the REPL would print it.
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(&self.0.inspect())
+    }
+}
@@ -1,51 +1,189 @@
 use std::fmt::Display;
 
-use super::{BlockStatement, Identifer, Node};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq)]
+use crate::token::{Position, Span};
+
+use super::{json, BlockStatement, Node, Parameter, Pattern};
+
+/// An expression, each carrying the `span` of source it was parsed from.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
 pub enum Expression {
-    Identifier(String),
-    IntegerLiteral(i64),
-    BooleanLiteral(bool),
+    #[serde(rename = "Identifier")]
+    Identifier { name: String, span: Span },
+    #[serde(rename = "IntegerLiteral")]
+    IntegerLiteral { value: i64, span: Span },
+    #[serde(rename = "FloatLiteral")]
+    FloatLiteral {
+        #[serde(with = "json::float")]
+        value: f64,
+        span: Span,
+    },
+    #[serde(rename = "StringLiteral")]
+    StringLiteral { value: String, span: Span },
+    #[serde(rename = "BooleanLiteral")]
+    BooleanLiteral { value: bool, span: Span },
+    #[serde(rename = "ArrayLiteral")]
+    ArrayLiteral {
+        elements: Vec<Expression>,
+        span: Span,
+    },
+    #[serde(rename = "HashLiteral")]
+    HashLiteral {
+        pairs: Vec<(Expression, Expression)>,
+        span: Span,
+    },
+    #[serde(rename = "Prefix")]
     Prefix {
         operator: String,
         right: Box<Expression>,
+        span: Span,
     },
+    #[serde(rename = "Infix")]
     Infix {
         left: Box<Expression>,
         operator: String,
         right: Box<Expression>,
+        span: Span,
     },
+    /// `if (a) { .. } else if (b) { .. } else { .. }`, with each condition
+    /// and the block it guards kept in order as one flat chain.
+    #[serde(rename = "If")]
     If {
-        condition: Box<Expression>,
-        consequence: Box<BlockStatement>,
+        branches: Vec<(Expression, BlockStatement)>,
         alternative: Option<Box<BlockStatement>>,
+        span: Span,
+    },
+    /// `match (subject) { pattern => body, ... }`, evaluating the body of
+    /// the first arm whose pattern matches and whose guard holds.
+    #[serde(rename = "Match")]
+    Match {
+        subject: Box<Expression>,
+        arms: Vec<MatchArm>,
+        span: Span,
+    },
+    /// `try { body } catch (pattern) { handler } finally { cleanup }`, with
+    /// at least one of `catch` and `finally`.
+    #[serde(rename = "Try")]
+    Try {
+        body: BlockStatement,
+        catch: Option<Box<(Pattern, BlockStatement)>>,
+        finally: Option<BlockStatement>,
+        span: Span,
     },
+    /// `condition ? consequence : alternative`
+    #[serde(rename = "Conditional")]
+    Conditional {
+        condition: Box<Expression>,
+        consequence: Box<Expression>,
+        alternative: Box<Expression>,
+        span: Span,
+    },
+    #[serde(rename = "FunctionLiteral")]
     FunctionLiteral {
-        parameters: Vec<Identifer>,
+        /// The name the function was declared or first bound with, if any.
+        name: Option<String>,
+        parameters: Vec<Parameter>,
         body: BlockStatement,
+        span: Span,
     },
+    #[serde(rename = "Call")]
     Call {
         function: Box<Expression>,
         arguments: Vec<Expression>,
+        /// Where the call starts, for stack traces.
+        position: Position,
+        span: Span,
+    },
+    /// `...xs` in a call's arguments, passing each element of `xs` as an
+    /// argument of its own.
+    #[serde(rename = "Spread")]
+    Spread { value: Box<Expression>, span: Span },
+    #[serde(rename = "Index")]
+    Index {
+        left: Box<Expression>,
+        index: Box<Expression>,
+        span: Span,
+    },
+    /// `object.property`, reading an export of a module or a string key of
+    /// a hash.
+    #[serde(rename = "Member")]
+    Member {
+        object: Box<Expression>,
+        property: String,
+        span: Span,
     },
+    #[serde(rename = "Range")]
+    Range {
+        start: Box<Expression>,
+        end: Box<Expression>,
+        inclusive: bool,
+        span: Span,
+    },
+    /// `target = value` or a compound form such as `target += value`. The
+    /// target is always an identifier or an index expression.
+    #[serde(rename = "Assign")]
+    Assign {
+        target: Box<Expression>,
+        operator: String,
+        value: Box<Expression>,
+        span: Span,
+    },
+}
+
+impl Expression {
+    pub fn span(&self) -> Span {
+        match self {
+            Expression::Identifier { span, .. }
+            | Expression::IntegerLiteral { span, .. }
+            | Expression::FloatLiteral { span, .. }
+            | Expression::StringLiteral { span, .. }
+            | Expression::BooleanLiteral { span, .. }
+            | Expression::ArrayLiteral { span, .. }
+            | Expression::HashLiteral { span, .. }
+            | Expression::Prefix { span, .. }
+            | Expression::Infix { span, .. }
+            | Expression::If { span, .. }
+            | Expression::Match { span, .. }
+            | Expression::Try { span, .. }
+            | Expression::Conditional { span, .. }
+            | Expression::FunctionLiteral { span, .. }
+            | Expression::Call { span, .. }
+            | Expression::Spread { span, .. }
+            | Expression::Index { span, .. }
+            | Expression::Member { span, .. }
+            | Expression::Range { span, .. }
+            | Expression::Assign { span, .. } => *span,
+        }
+    }
 }
 
 impl Node for Expression {
     fn token_literal(&self) -> String {
         match self {
-            Expression::Identifier(value) => value.to_string(),
-            Expression::IntegerLiteral(value) => value.to_string(),
-            Expression::BooleanLiteral(value) => value.to_string(),
-            Expression::Prefix { operator, right: _ } => operator.to_string(),
-            Expression::Infix {
-                left: _,
-                operator,
-                right: _,
-            } => operator.to_string(),
+            Expression::Identifier { name, .. } => name.to_string(),
+            Expression::IntegerLiteral { value, .. } => value.to_string(),
+            Expression::FloatLiteral { value, .. } => value.to_string(),
+            Expression::StringLiteral { value, .. } => value.to_string(),
+            Expression::BooleanLiteral { value, .. } => value.to_string(),
+            Expression::ArrayLiteral { .. } => "[".to_string(),
+            Expression::HashLiteral { .. } => "{".to_string(),
+            Expression::Prefix { operator, .. } => operator.to_string(),
+            Expression::Infix { operator, .. } => operator.to_string(),
             Expression::If { .. } => "if".to_string(),
+            Expression::Match { .. } => "match".to_string(),
+            Expression::Try { .. } => "try".to_string(),
+            Expression::Conditional { .. } => "?".to_string(),
             Expression::FunctionLiteral { .. } => "fn".to_string(),
             Expression::Call { .. } => "(".to_string(),
+            Expression::Spread { .. } => "...".to_string(),
+            Expression::Index { .. } => "[".to_string(),
+            Expression::Member { .. } => ".".to_string(),
+            Expression::Range { inclusive, .. } => {
+                if *inclusive { "..=" } else { ".." }.to_string()
+            }
+            Expression::Assign { operator, .. } => operator.to_string(),
         }
     }
 }
@@ -53,35 +191,97 @@ impl Node for Expression {
 impl Display for Expression {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Expression::Prefix { operator, right } => write!(f, "({operator}{right})"),
+            Expression::FloatLiteral { value, .. } => write!(f, "{value:?}"),
+            Expression::StringLiteral { value, .. } => write!(f, "\"{}\"", escape(value)),
+            Expression::ArrayLiteral { elements, .. } => {
+                let elements = elements
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                write!(f, "[{elements}]")
+            }
+            Expression::HashLiteral { pairs, .. } => {
+                let pairs = pairs
+                    .iter()
+                    .map(|(key, value)| format!("{key}: {value}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                write!(f, "{{{pairs}}}")
+            }
+            Expression::Prefix {
+                operator, right, ..
+            } => write!(f, "({operator}{right})"),
             Expression::Infix {
                 left,
                 operator,
                 right,
+                ..
             } => write!(f, "({left} {operator} {right})"),
             Expression::If {
-                condition,
-                consequence,
+                branches,
                 alternative,
+                ..
             } => {
+                let branches = branches
+                    .iter()
+                    .map(|(condition, consequence)| format!("if ({condition}) {{ {consequence} }}"))
+                    .collect::<Vec<_>>()
+                    .join(" else ");
+                write!(f, "{branches}")?;
+
                 if let Some(alternative) = alternative {
-                    write!(f, "if{condition} {consequence}else {alternative}")
-                } else {
-                    write!(f, "if{condition} {consequence}")
+                    write!(f, " else {{ {alternative} }}")?;
                 }
+                Ok(())
+            }
+            Expression::Try {
+                body,
+                catch,
+                finally,
+                ..
+            } => {
+                write!(f, "try {{ {body} }}")?;
+                if let Some((error, handler)) = catch.as_deref() {
+                    write!(f, " catch ({error}) {{ {handler} }}")?;
+                }
+                if let Some(cleanup) = finally {
+                    write!(f, " finally {{ {cleanup} }}")?;
+                }
+                Ok(())
+            }
+            Expression::Match { subject, arms, .. } => {
+                let arms = arms
+                    .iter()
+                    .map(|arm| arm.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                write!(f, "match ({subject}) {{ {arms} }}")
             }
-            Expression::FunctionLiteral { parameters, body } => {
+            Expression::Conditional {
+                condition,
+                consequence,
+                alternative,
+                ..
+            } => write!(f, "({condition} ? {consequence} : {alternative})"),
+            Expression::FunctionLiteral {
+                parameters, body, ..
+            } => {
                 let parameters = parameters
                     .iter()
-                    .map(|p| p.token_literal())
+                    .map(|p| p.to_string())
                     .collect::<Vec<_>>()
                     .join(", ");
 
-                write!(f, "fn({parameters}) {body}")
+                write!(f, "fn({parameters}) {{ {body} }}")
             }
             Expression::Call {
                 function,
                 arguments,
+                ..
             } => {
                 let arguments = arguments
                     .iter()
@@ -91,7 +291,70 @@ impl Display for Expression {
 
                 write!(f, "{function}({arguments})")
             }
+            Expression::Spread { value, .. } => write!(f, "...{value}"),
+            Expression::Index { left, index, .. } => write!(f, "({left}[{index}])"),
+            Expression::Member {
+                object, property, ..
+            } => write!(f, "({object}.{property})"),
+            Expression::Range {
+                start,
+                end,
+                inclusive,
+                ..
+            } => {
+                let operator = if *inclusive { "..=" } else { ".." };
+                write!(f, "({start}{operator}{end})")
+            }
+            Expression::Assign {
+                target,
+                operator,
+                value,
+                ..
+            } => write!(f, "({target} {operator} {value})"),
             _ => write!(f, "{}", self.token_literal()),
         }
     }
 }
+
+/// `pattern if guard => body` in a `match` expression.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "MatchArm")]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expression>,
+    pub body: BlockStatement,
+}
+
+impl MatchArm {
+    /// Whether this arm matches every value, so that arms after it are
+    /// never reached.
+    pub fn is_catch_all(&self) -> bool {
+        self.guard.is_none() && matches!(self.pattern, Pattern::Wildcard | Pattern::Binding(_))
+    }
+}
+
+impl Display for MatchArm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.pattern)?;
+        if let Some(guard) = &self.guard {
+            write!(f, " if {guard}")?;
+        }
+        write!(f, " => {{ {} }}", self.body)
+    }
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            '\0' => escaped.push_str("\\0"),
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
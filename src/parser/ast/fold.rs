@@ -0,0 +1,454 @@
+//! Rewriting the syntax tree.
+//!
+//! Implement [`Fold`] and override the methods for the nodes to rewrite.
+//! Each method takes a node and returns its replacement. The defaults call
+//! the matching `walk_*` function, which rebuilds the node from its folded
+//! children.
+
+use super::{
+    BlockStatement, Expression, Identifer, MatchArm, Parameter, Pattern, Program, Statement,
+};
+
+pub trait Fold {
+    fn fold_program(&mut self, program: Program) -> Program {
+        walk_program(self, program)
+    }
+
+    fn fold_statement(&mut self, statement: Statement) -> Statement {
+        walk_statement(self, statement)
+    }
+
+    fn fold_block_statement(&mut self, block: BlockStatement) -> BlockStatement {
+        walk_block_statement(self, block)
+    }
+
+    fn fold_expression(&mut self, expression: Expression) -> Expression {
+        walk_expression(self, expression)
+    }
+
+    fn fold_match_arm(&mut self, arm: MatchArm) -> MatchArm {
+        walk_match_arm(self, arm)
+    }
+
+    fn fold_pattern(&mut self, pattern: Pattern) -> Pattern {
+        walk_pattern(self, pattern)
+    }
+
+    fn fold_parameter(&mut self, parameter: Parameter) -> Parameter {
+        walk_parameter(self, parameter)
+    }
+
+    /// Called for names that are declared, as with
+    /// [`Visitor::visit_identifier`](super::visit::Visitor::visit_identifier).
+    fn fold_identifier(&mut self, identifier: Identifer) -> Identifer {
+        identifier
+    }
+}
+
+pub fn walk_program<F: Fold + ?Sized>(folder: &mut F, program: Program) -> Program {
+    Program {
+        statements: program
+            .statements
+            .into_iter()
+            .map(|statement| folder.fold_statement(statement))
+            .collect(),
+        span: program.span,
+    }
+}
+
+pub fn walk_statement<F: Fold + ?Sized>(folder: &mut F, statement: Statement) -> Statement {
+    match statement {
+        Statement::Let {
+            pattern,
+            mutable,
+            value,
+            span,
+        } => Statement::Let {
+            pattern: folder.fold_pattern(pattern),
+            mutable,
+            value: folder.fold_expression(value),
+            span,
+        },
+        Statement::Return { value, span } => Statement::Return {
+            value: folder.fold_expression(value),
+            span,
+        },
+        Statement::Throw { value, span } => Statement::Throw {
+            value: folder.fold_expression(value),
+            span,
+        },
+        Statement::Function {
+            name,
+            parameters,
+            body,
+            span,
+        } => Statement::Function {
+            name: folder.fold_identifier(name),
+            parameters: fold_parameters(folder, parameters),
+            body: folder.fold_block_statement(body),
+            span,
+        },
+        Statement::While {
+            condition,
+            body,
+            span,
+        } => Statement::While {
+            condition: folder.fold_expression(condition),
+            body: folder.fold_block_statement(body),
+            span,
+        },
+        Statement::For {
+            variable,
+            iterable,
+            body,
+            span,
+        } => Statement::For {
+            variable: folder.fold_identifier(variable),
+            iterable: folder.fold_expression(iterable),
+            body: folder.fold_block_statement(body),
+            span,
+        },
+        Statement::Break { span } => Statement::Break { span },
+        Statement::Continue { span } => Statement::Continue { span },
+        Statement::Import { path, alias, span } => Statement::Import {
+            path,
+            alias: folder.fold_identifier(alias),
+            span,
+        },
+        Statement::Export { statement, span } => Statement::Export {
+            statement: Box::new(folder.fold_statement(*statement)),
+            span,
+        },
+        Statement::Expression { span, expression } => Statement::Expression {
+            span,
+            expression: folder.fold_expression(expression),
+        },
+    }
+}
+
+pub fn walk_block_statement<F: Fold + ?Sized>(
+    folder: &mut F,
+    block: BlockStatement,
+) -> BlockStatement {
+    BlockStatement {
+        statements: block
+            .statements
+            .into_iter()
+            .map(|statement| folder.fold_statement(statement))
+            .collect(),
+        span: block.span,
+    }
+}
+
+pub fn walk_expression<F: Fold + ?Sized>(folder: &mut F, expression: Expression) -> Expression {
+    match expression {
+        Expression::Identifier { .. }
+        | Expression::IntegerLiteral { .. }
+        | Expression::FloatLiteral { .. }
+        | Expression::StringLiteral { .. }
+        | Expression::BooleanLiteral { .. } => expression,
+        Expression::ArrayLiteral { elements, span } => Expression::ArrayLiteral {
+            elements: fold_expressions(folder, elements),
+            span,
+        },
+        Expression::HashLiteral { pairs, span } => Expression::HashLiteral {
+            pairs: pairs
+                .into_iter()
+                .map(|(key, value)| (folder.fold_expression(key), folder.fold_expression(value)))
+                .collect(),
+            span,
+        },
+        Expression::Prefix {
+            operator,
+            right,
+            span,
+        } => Expression::Prefix {
+            operator,
+            right: fold_boxed(folder, *right),
+            span,
+        },
+        Expression::Infix {
+            left,
+            operator,
+            right,
+            span,
+        } => Expression::Infix {
+            left: fold_boxed(folder, *left),
+            operator,
+            right: fold_boxed(folder, *right),
+            span,
+        },
+        Expression::If {
+            branches,
+            alternative,
+            span,
+        } => Expression::If {
+            branches: branches
+                .into_iter()
+                .map(|(condition, consequence)| {
+                    (
+                        folder.fold_expression(condition),
+                        folder.fold_block_statement(consequence),
+                    )
+                })
+                .collect(),
+            alternative: alternative
+                .map(|alternative| Box::new(folder.fold_block_statement(*alternative))),
+            span,
+        },
+        Expression::Match {
+            subject,
+            arms,
+            span,
+        } => Expression::Match {
+            subject: fold_boxed(folder, *subject),
+            arms: arms
+                .into_iter()
+                .map(|arm| folder.fold_match_arm(arm))
+                .collect(),
+            span,
+        },
+        Expression::Try {
+            body,
+            catch,
+            finally,
+            span,
+        } => Expression::Try {
+            body: folder.fold_block_statement(body),
+            catch: catch.map(|catch| {
+                let (pattern, handler) = *catch;
+                Box::new((
+                    folder.fold_pattern(pattern),
+                    folder.fold_block_statement(handler),
+                ))
+            }),
+            finally: finally.map(|cleanup| folder.fold_block_statement(cleanup)),
+            span,
+        },
+        Expression::Conditional {
+            condition,
+            consequence,
+            alternative,
+            span,
+        } => Expression::Conditional {
+            condition: fold_boxed(folder, *condition),
+            consequence: fold_boxed(folder, *consequence),
+            alternative: fold_boxed(folder, *alternative),
+            span,
+        },
+        Expression::FunctionLiteral {
+            name,
+            parameters,
+            body,
+            span,
+        } => Expression::FunctionLiteral {
+            name,
+            parameters: fold_parameters(folder, parameters),
+            body: folder.fold_block_statement(body),
+            span,
+        },
+        Expression::Call {
+            function,
+            arguments,
+            position,
+            span,
+        } => Expression::Call {
+            function: fold_boxed(folder, *function),
+            arguments: fold_expressions(folder, arguments),
+            position,
+            span,
+        },
+        Expression::Spread { value, span } => Expression::Spread {
+            value: fold_boxed(folder, *value),
+            span,
+        },
+        Expression::Index { left, index, span } => Expression::Index {
+            left: fold_boxed(folder, *left),
+            index: fold_boxed(folder, *index),
+            span,
+        },
+        Expression::Member {
+            object,
+            property,
+            span,
+        } => Expression::Member {
+            object: fold_boxed(folder, *object),
+            property,
+            span,
+        },
+        Expression::Range {
+            start,
+            end,
+            inclusive,
+            span,
+        } => Expression::Range {
+            start: fold_boxed(folder, *start),
+            end: fold_boxed(folder, *end),
+            inclusive,
+            span,
+        },
+        Expression::Assign {
+            target,
+            operator,
+            value,
+            span,
+        } => Expression::Assign {
+            target: fold_boxed(folder, *target),
+            operator,
+            value: fold_boxed(folder, *value),
+            span,
+        },
+    }
+}
+
+pub fn walk_match_arm<F: Fold + ?Sized>(folder: &mut F, arm: MatchArm) -> MatchArm {
+    MatchArm {
+        pattern: folder.fold_pattern(arm.pattern),
+        guard: arm.guard.map(|guard| folder.fold_expression(guard)),
+        body: folder.fold_block_statement(arm.body),
+    }
+}
+
+pub fn walk_pattern<F: Fold + ?Sized>(folder: &mut F, pattern: Pattern) -> Pattern {
+    match pattern {
+        Pattern::Wildcard => Pattern::Wildcard,
+        Pattern::Literal(literal) => Pattern::Literal(folder.fold_expression(literal)),
+        Pattern::Binding(name) => Pattern::Binding(folder.fold_identifier(name)),
+        Pattern::Array { elements, rest } => Pattern::Array {
+            elements: elements
+                .into_iter()
+                .map(|element| folder.fold_pattern(element))
+                .collect(),
+            rest: rest.map(|rest| Box::new(folder.fold_pattern(*rest))),
+        },
+        Pattern::Hash(pairs) => Pattern::Hash(
+            pairs
+                .into_iter()
+                .map(|(key, pattern)| (folder.fold_expression(key), folder.fold_pattern(pattern)))
+                .collect(),
+        ),
+    }
+}
+
+pub fn walk_parameter<F: Fold + ?Sized>(folder: &mut F, parameter: Parameter) -> Parameter {
+    Parameter {
+        pattern: folder.fold_pattern(parameter.pattern),
+        default: parameter
+            .default
+            .map(|default| folder.fold_expression(default)),
+        rest: parameter.rest,
+    }
+}
+
+fn fold_boxed<F: Fold + ?Sized>(folder: &mut F, expression: Expression) -> Box<Expression> {
+    Box::new(folder.fold_expression(expression))
+}
+
+fn fold_expressions<F: Fold + ?Sized>(
+    folder: &mut F,
+    expressions: Vec<Expression>,
+) -> Vec<Expression> {
+    expressions
+        .into_iter()
+        .map(|expression| folder.fold_expression(expression))
+        .collect()
+}
+
+fn fold_parameters<F: Fold + ?Sized>(folder: &mut F, parameters: Vec<Parameter>) -> Vec<Parameter> {
+    parameters
+        .into_iter()
+        .map(|parameter| folder.fold_parameter(parameter))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::ast::{parse, Expression, Identifer};
+
+    use super::{walk_expression, Fold};
+
+    /// Folds integer additions and multiplications of literals.
+    struct ConstantFolder;
+
+    impl Fold for ConstantFolder {
+        fn fold_expression(&mut self, expression: Expression) -> Expression {
+            match walk_expression(self, expression) {
+                Expression::Infix {
+                    left,
+                    operator,
+                    right,
+                    span,
+                } => match (*left, operator.as_str(), *right) {
+                    (
+                        Expression::IntegerLiteral { value: a, .. },
+                        "+" | "*",
+                        Expression::IntegerLiteral { value: b, .. },
+                    ) => Expression::IntegerLiteral {
+                        value: if operator == "+" { a + b } else { a * b },
+                        span,
+                    },
+                    (left, _, right) => Expression::Infix {
+                        left: Box::new(left),
+                        operator,
+                        right: Box::new(right),
+                        span,
+                    },
+                },
+                expression => expression,
+            }
+        }
+    }
+
+    /// Renames declarations and uses of one name.
+    struct Rename(&'static str, &'static str);
+
+    impl Fold for Rename {
+        fn fold_expression(&mut self, expression: Expression) -> Expression {
+            match expression {
+                Expression::Identifier { name, span } if name == self.0 => Expression::Identifier {
+                    name: self.1.to_string(),
+                    span,
+                },
+                expression => walk_expression(self, expression),
+            }
+        }
+
+        fn fold_identifier(&mut self, identifier: Identifer) -> Identifer {
+            if identifier.value == self.0 {
+                Identifer {
+                    value: self.1.to_string(),
+                    ..identifier
+                }
+            } else {
+                identifier
+            }
+        }
+    }
+
+    #[test]
+    fn folds_rebuild_the_tree() {
+        // Arrange
+        let mut constants = ConstantFolder;
+        let mut rename = Rename("x", "value");
+        let tests: Vec<(&str, &mut dyn Fold, &str)> = vec![
+            (
+                "let x = 1 + 2 * 3; f(x + (4 * 5), [2 + 2])",
+                &mut constants,
+                "let x = 7;f((x + 20), [4])",
+            ),
+            (
+                "fn f([x], y = x) { match (x) { x if x > 0 => x + y } }",
+                &mut rename,
+                "fn f([value], y = value) { match (value) { value if (value > 0) => { (value + y) } } }",
+            ),
+        ];
+
+        for (input, fold, expected) in tests {
+            // Act
+            let folded = fold.fold_program(parse(input));
+
+            // Assert
+            assert_eq!(folded.to_string(), expected, "{input}");
+        }
+    }
+}
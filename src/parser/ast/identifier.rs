@@ -1,18 +1,22 @@
 use std::fmt::Display;
 
-use crate::token::Token;
+use serde::{Deserialize, Serialize};
+
+use crate::token::{Position, Span};
 
 use super::Node;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "Name")]
 pub struct Identifer {
-    pub token: Token,
     pub value: String,
+    pub span: Span,
+    pub position: Position,
 }
 
 impl Node for Identifer {
     fn token_literal(&self) -> String {
-        self.token.literal.to_string()
+        self.value.to_string()
     }
 }
 
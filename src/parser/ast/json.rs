@@ -0,0 +1,312 @@
+//! A JSON form of the syntax tree, for external tools and snapshot tests.
+//!
+//! Every node is an object whose `"type"` names it. The names are written
+//! out with `#[serde(rename)]` on each node rather than taken from the Rust
+//! types, so the format only changes along with the syntax:
+//!
+//! - `Program` and `Block` hold a list of `statements` and their `span`.
+//! - Statements are `Let`, `Return`, `FunctionDeclaration`, `While`, `For`,
+//!   `Break`, `Continue`, `Throw`, `Import`, `Export` and `Expression`, with
+//!   their fields, including their `span`, under `"value"`.
+//! - Expressions are `Identifier`, `IntegerLiteral`, `FloatLiteral`,
+//!   `StringLiteral`, `BooleanLiteral`, `ArrayLiteral`, `HashLiteral`,
+//!   `Prefix`, `Infix`, `If`, `Match`, `Try`, `Conditional`,
+//!   `FunctionLiteral`, `Call`, `Spread`, `Index`, `Member`, `Range` and
+//!   `Assign`. Their fields are under `"value"`, and always include the
+//!   `span` of source they were parsed from as `start` and `end` byte
+//!   offsets.
+//! - Patterns are `Wildcard`, `Literal`, `Binding`, `ArrayPattern` and
+//!   `HashPattern`, with their fields under `"value"`.
+//! - `Name` is a declared name, with its `span` and `position`. `MatchArm`
+//!   and `Parameter` keep their fields next to the tag.
+//!
+//! Floats that are not finite, which JSON numbers cannot hold, are written
+//! as the strings `"NaN"`, `"Infinity"` and `"-Infinity"`.
+
+use super::Program;
+
+pub fn to_json(program: &Program) -> String {
+    serde_json::to_string_pretty(program).expect("syntax trees serialize to JSON")
+}
+
+pub fn from_json(json: &str) -> Result<Program, serde_json::Error> {
+    serde_json::from_str(json)
+}
+
+/// Serializes a float field, writing non-finite values as strings.
+pub(super) mod float {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        if value.is_nan() {
+            serializer.serialize_str("NaN")
+        } else if value.is_infinite() {
+            serializer.serialize_str(if *value > 0.0 {
+                "Infinity"
+            } else {
+                "-Infinity"
+            })
+        } else {
+            serializer.serialize_f64(*value)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Float {
+            Number(f64),
+            Name(String),
+        }
+
+        match Float::deserialize(deserializer)? {
+            Float::Number(value) => Ok(value),
+            Float::Name(name) => match name.as_str() {
+                "NaN" => Ok(f64::NAN),
+                "Infinity" => Ok(f64::INFINITY),
+                "-Infinity" => Ok(f64::NEG_INFINITY),
+                _ => Err(D::Error::custom(format!("invalid float {name:?}"))),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::ast::visit::{self, Visitor};
+    use crate::parser::ast::{parse, BlockStatement, Expression, Program, Statement};
+    use crate::token::Span;
+
+    use super::{from_json, to_json};
+
+    #[test]
+    fn json_has_type_tags_and_spans() {
+        // Arrange
+        let program = parse("let x = f(1);\nx");
+        let expected = serde_json::json!({
+            "type": "Program",
+            "statements": [
+                {
+                    "type": "Let",
+                    "value": {
+                        "pattern": {
+                            "type": "Binding",
+                            "value": {
+                                "type": "Name",
+                                "value": "x",
+                                "span": {"start": 4, "end": 5},
+                                "position": {"line": 1, "column": 5}
+                            }
+                        },
+                        "mutable": false,
+                        "value": {
+                            "type": "Call",
+                            "value": {
+                                "function": {
+                                    "type": "Identifier",
+                                    "value": {"name": "f", "span": {"start": 8, "end": 9}}
+                                },
+                                "arguments": [{
+                                    "type": "IntegerLiteral",
+                                    "value": {"value": 1, "span": {"start": 10, "end": 11}}
+                                }],
+                                "position": {"line": 1, "column": 9},
+                                "span": {"start": 8, "end": 12}
+                            }
+                        },
+                        "span": {"start": 0, "end": 13}
+                    }
+                },
+                {
+                    "type": "Expression",
+                    "value": {
+                        "span": {"start": 14, "end": 15},
+                        "expression": {
+                            "type": "Identifier",
+                            "value": {"name": "x", "span": {"start": 14, "end": 15}}
+                        }
+                    }
+                }
+            ],
+            "span": {"start": 0, "end": 15}
+        });
+
+        // Act
+        let json: serde_json::Value = serde_json::from_str(&to_json(&program)).unwrap();
+
+        // Assert
+        assert_eq!(json, expected);
+    }
+
+    #[test]
+    fn json_round_trips() {
+        // Arrange
+        let tests = vec![
+            "let mut x = -1.5 + 2 * 3; x += 1; x",
+            r#"import "lib.gb" as lib; export fn f([a, ...rest], b = {"k": 1}) { return lib.g(...rest)[0]; }"#,
+            "for (i in 0..=10) { if (i == 2) { continue } else if (i > 8) { break } else { puts(i) } }",
+            r#"while (true) { throw {"message": "no"}; }"#,
+            r#"match (x) { [1, {"a": y}] if y => y, "s" => fn(z) { z ? true : false }, _ => 0 }"#,
+            "try { f() } catch ([e]) { e } finally { g() }",
+        ];
+
+        for input in tests {
+            let program = parse(input);
+
+            // Act
+            let round_tripped = from_json(&to_json(&program)).unwrap();
+
+            // Assert
+            assert_eq!(round_tripped, program, "{input}");
+            assert_eq!(round_tripped.span, Span::new(0, input.len()), "{input}");
+            assert_eq!(
+                StatementSpans::of(&round_tripped),
+                StatementSpans::of(&program),
+                "{input}"
+            );
+        }
+    }
+
+    /// The spans of every statement and block, in source order.
+    #[derive(Default)]
+    struct StatementSpans(Vec<Span>);
+
+    impl StatementSpans {
+        fn of(program: &Program) -> Vec<Span> {
+            let mut spans = StatementSpans::default();
+            spans.visit_program(program);
+            spans.0
+        }
+    }
+
+    impl Visitor for StatementSpans {
+        fn visit_statement(&mut self, statement: &Statement) {
+            self.0.push(statement.span());
+            visit::walk_statement(self, statement);
+        }
+
+        fn visit_block_statement(&mut self, block: &BlockStatement) {
+            self.0.push(block.span);
+            visit::walk_block_statement(self, block);
+        }
+    }
+
+    #[test]
+    fn statements_and_blocks_have_spans() {
+        // Arrange
+        let input = "let x = 1;\nwhile (x) { break; }\nfn f() { return 2 }\nexport let y = 3;\nx";
+        let expected = vec![
+            "let x = 1;",
+            "while (x) { break; }",
+            "{ break; }",
+            "break;",
+            "fn f() { return 2 }",
+            "{ return 2 }",
+            "return 2",
+            "export let y = 3;",
+            "let y = 3;",
+            "x",
+        ];
+
+        // Act
+        let spans = StatementSpans::of(&parse(input));
+
+        // Assert
+        let sources = spans
+            .iter()
+            .map(|span| &input[span.start..span.end])
+            .collect::<Vec<_>>();
+        assert_eq!(sources, expected);
+    }
+
+    #[test]
+    fn expressions_have_spans() {
+        // Arrange
+        let tests = vec![
+            ("-a + (b * c)[0]", "-a + (b * c)[0]"),
+            ("(b * c)[0]", "(b * c)[0]"),
+            ("(b * c)", "b * c"),
+            ("f(...xs)", "f(...xs)"),
+            ("x = y ? [1] : {}", "x = y ? [1] : {}"),
+            ("if (a) { b } else { c }", "if (a) { b } else { c }"),
+            ("m.k..=2", "m.k..=2"),
+        ];
+
+        for (input, expected) in tests {
+            let program = parse(input);
+            let Statement::Expression { expression, .. } = &program.statements[0] else {
+                panic!("{input} is not an expression statement");
+            };
+
+            // Act
+            let span = expression.span();
+
+            // Assert
+            assert_eq!(&input[span.start..span.end], expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn non_finite_floats_are_strings() {
+        // Arrange
+        let tests = vec![
+            (f64::INFINITY, "\"Infinity\""),
+            (f64::NEG_INFINITY, "\"-Infinity\""),
+            (f64::NAN, "\"NaN\""),
+            (1.5, "1.5"),
+        ];
+
+        for (value, expected) in tests {
+            let program = Program {
+                statements: vec![Statement::Return {
+                    value: Expression::FloatLiteral {
+                        value,
+                        span: Span::new(0, 1),
+                    },
+                    span: Span::new(0, 1),
+                }],
+                span: Span::new(0, 1),
+            };
+
+            // Act
+            let json = to_json(&program);
+            let round_tripped = from_json(&json).unwrap();
+
+            // Assert
+            assert!(json.contains(&format!("\"value\": {expected}")), "{json}");
+            let Statement::Return {
+                value: Expression::FloatLiteral { value: read, .. },
+                ..
+            } = &round_tripped.statements[0]
+            else {
+                panic!("{json} did not read back as a float");
+            };
+            assert_eq!(read.to_bits(), value.to_bits(), "{json}");
+        }
+    }
+
+    #[test]
+    fn invalid_json_is_an_error() {
+        // Arrange
+        let tests = vec![
+            ("42", "invalid type: integer"),
+            (
+                r#"{"type": "Program", "statements": [{"type": "Loop"}]}"#,
+                "unknown variant `Loop`",
+            ),
+            (
+                r#"{"type": "Program", "statements": [{"type": "Return", "value": {"value": {"type": "FloatLiteral", "value": {"value": "Inf", "span": {"start": 0, "end": 1}}}, "span": {"start": 0, "end": 1}}}], "span": {"start": 0, "end": 1}}"#,
+                "invalid float \"Inf\"",
+            ),
+        ];
+
+        for (input, expected) in tests {
+            // Act
+            let error = from_json(input).unwrap_err();
+
+            // Assert
+            assert!(error.to_string().contains(expected), "{error}");
+        }
+    }
+}
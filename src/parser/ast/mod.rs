@@ -1,11 +1,29 @@
 pub mod expression;
+pub mod fold;
 pub mod identifier;
+pub mod json;
 pub mod node;
+pub mod parameter;
+pub mod pattern;
 pub mod program;
 pub mod statement;
+pub mod visit;
 
 pub use expression::*;
+pub use fold::Fold;
 pub use identifier::*;
 pub use node::*;
+pub use parameter::*;
+pub use pattern::*;
 pub use program::*;
 pub use statement::*;
+pub use visit::Visitor;
+
+/// Parses `input` for the AST tests, failing the test on any parse error.
+#[cfg(test)]
+pub(crate) fn parse(input: &str) -> Program {
+    let mut parser = crate::parser::Parser::new(crate::lexer::Lexer::new(input));
+    let program = parser.parse_program();
+    assert!(parser.errors.is_empty(), "{:?}", parser.errors);
+    program
+}
@@ -0,0 +1,35 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Expression, Node, Pattern};
+
+/// A function parameter: a pattern (`x`, `[a, b]`, `{name}`), optionally
+/// with a default value (`y = 10`), or a trailing rest parameter (`...rest`)
+/// that collects the remaining arguments.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "Parameter")]
+pub struct Parameter {
+    pub pattern: Pattern,
+    pub default: Option<Expression>,
+    pub rest: bool,
+}
+
+impl Node for Parameter {
+    fn token_literal(&self) -> String {
+        self.pattern.token_literal()
+    }
+}
+
+impl Display for Parameter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.rest {
+            write!(f, "...")?;
+        }
+        write!(f, "{}", self.pattern)?;
+        if let Some(default) = &self.default {
+            write!(f, " = {default}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Expression, Identifer, Node};
+
+/// The shape a value is tested against, binding names to the parts that
+/// match.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum Pattern {
+    /// `_`, matching anything without binding it.
+    #[serde(rename = "Wildcard")]
+    Wildcard,
+    /// A number, string or boolean, matching values equal to it.
+    #[serde(rename = "Literal")]
+    Literal(Expression),
+    /// A name, matching anything and binding it to the value.
+    #[serde(rename = "Binding")]
+    Binding(Identifer),
+    /// `[a, b, ...rest]`, matching arrays element by element. Without a
+    /// rest pattern the lengths must be equal.
+    #[serde(rename = "ArrayPattern")]
+    Array {
+        elements: Vec<Pattern>,
+        rest: Option<Box<Pattern>>,
+    },
+    /// `{"k": v}`, matching hashes that have every listed key.
+    #[serde(rename = "HashPattern")]
+    Hash(Vec<(Expression, Pattern)>),
+}
+
+impl Pattern {
+    /// The names this pattern binds, in order.
+    pub fn bindings(&self) -> Vec<&Identifer> {
+        match self {
+            Pattern::Wildcard | Pattern::Literal(_) => vec![],
+            Pattern::Binding(name) => vec![name],
+            Pattern::Array { elements, rest } => elements
+                .iter()
+                .chain(rest.as_deref())
+                .flat_map(Pattern::bindings)
+                .collect(),
+            Pattern::Hash(pairs) => pairs
+                .iter()
+                .flat_map(|(_, pattern)| pattern.bindings())
+                .collect(),
+        }
+    }
+}
+
+impl Node for Pattern {
+    fn token_literal(&self) -> String {
+        match self {
+            Pattern::Wildcard => "_".to_string(),
+            Pattern::Literal(value) => value.token_literal(),
+            Pattern::Binding(name) => name.token_literal(),
+            Pattern::Array { .. } => "[".to_string(),
+            Pattern::Hash(_) => "{".to_string(),
+        }
+    }
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pattern::Wildcard => write!(f, "_"),
+            // Negative numbers are written without the parentheses the
+            // prefix expression adds, as patterns do not allow them.
+            Pattern::Literal(Expression::Prefix {
+                operator, right, ..
+            }) => {
+                write!(f, "{operator}{right}")
+            }
+            Pattern::Literal(value) => write!(f, "{value}"),
+            Pattern::Binding(name) => write!(f, "{name}"),
+            Pattern::Array { elements, rest } => {
+                let mut elements = elements.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+                if let Some(rest) = rest {
+                    elements.push(format!("...{rest}"));
+                }
+
+                write!(f, "[{}]", elements.join(", "))
+            }
+            Pattern::Hash(pairs) => {
+                let pairs = pairs
+                    .iter()
+                    .map(|(key, value)| format!("{key}: {value}"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                write!(f, "{{{pairs}}}")
+            }
+        }
+    }
+}
@@ -1,9 +1,17 @@
 use std::fmt::Display;
 
-use super::{Node, Statement};
+use serde::{Deserialize, Serialize};
 
+use crate::token::Span;
+
+use super::{statement::write_statements, Node, Statement};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "Program")]
 pub struct Program {
     pub statements: Vec<Statement>,
+    /// The whole source, including any leading and trailing whitespace.
+    pub span: Span,
 }
 
 impl Node for Program {
@@ -18,10 +26,6 @@ impl Node for Program {
 
 impl Display for Program {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for statement in &self.statements {
-            write!(f, "{statement}")?;
-        }
-
-        Ok(())
+        write_statements(f, &self.statements)
     }
 }
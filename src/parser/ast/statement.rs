@@ -1,31 +1,101 @@
 use std::fmt::Display;
 
-use crate::token::Token;
+use serde::{Deserialize, Serialize};
 
-use super::{Expression, Identifer, Node};
+use crate::token::Span;
 
-#[derive(Debug, PartialEq)]
+use super::{Expression, Identifer, Node, Parameter, Pattern};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
 pub enum Statement {
+    #[serde(rename = "Let")]
     Let {
-        name: Identifer,
+        pattern: Pattern,
+        mutable: bool,
         value: Expression,
+        span: Span,
+    },
+    #[serde(rename = "Return")]
+    Return { value: Expression, span: Span },
+    /// `fn name(parameters) { body }`, binding the function to `name`.
+    #[serde(rename = "FunctionDeclaration")]
+    Function {
+        name: Identifer,
+        parameters: Vec<Parameter>,
+        body: BlockStatement,
+        span: Span,
+    },
+    #[serde(rename = "While")]
+    While {
+        condition: Expression,
+        body: BlockStatement,
+        span: Span,
+    },
+    #[serde(rename = "For")]
+    For {
+        variable: Identifer,
+        iterable: Expression,
+        body: BlockStatement,
+        span: Span,
     },
-    Return(Expression),
-    Expression {
-        token: Token,
-        expression: Expression,
+    #[serde(rename = "Break")]
+    Break { span: Span },
+    #[serde(rename = "Continue")]
+    Continue { span: Span },
+    /// `throw value;`
+    #[serde(rename = "Throw")]
+    Throw { value: Expression, span: Span },
+    /// `import "path" as alias;`
+    #[serde(rename = "Import")]
+    Import {
+        path: String,
+        alias: Identifer,
+        span: Span,
     },
+    /// `export` in front of a top level `let` or `fn` declaration.
+    #[serde(rename = "Export")]
+    Export {
+        statement: Box<Statement>,
+        span: Span,
+    },
+    #[serde(rename = "Expression")]
+    Expression { span: Span, expression: Expression },
+}
+
+impl Statement {
+    /// The source the statement was parsed from, including its `;`.
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::Let { span, .. }
+            | Statement::Return { span, .. }
+            | Statement::Function { span, .. }
+            | Statement::While { span, .. }
+            | Statement::For { span, .. }
+            | Statement::Break { span }
+            | Statement::Continue { span }
+            | Statement::Throw { span, .. }
+            | Statement::Import { span, .. }
+            | Statement::Export { span, .. }
+            | Statement::Expression { span, .. } => *span,
+        }
+    }
 }
 
 impl Node for Statement {
     fn token_literal(&self) -> String {
         match self {
             Self::Let { .. } => "let".to_string(),
-            Self::Return(_) => "return".to_string(),
-            Self::Expression {
-                token,
-                expression: _,
-            } => token.literal.to_string(),
+            Self::Return { .. } => "return".to_string(),
+            Self::Function { .. } => "fn".to_string(),
+            Self::While { .. } => "while".to_string(),
+            Self::For { .. } => "for".to_string(),
+            Self::Break { .. } => "break".to_string(),
+            Self::Continue { .. } => "continue".to_string(),
+            Self::Throw { .. } => "throw".to_string(),
+            Self::Import { .. } => "import".to_string(),
+            Self::Export { .. } => "export".to_string(),
+            Self::Expression { expression, .. } => expression.token_literal(),
         }
     }
 }
@@ -33,21 +103,57 @@ impl Node for Statement {
 impl Display for Statement {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Statement::Let { name, value } => {
-                write!(f, "let {} = {};", name, value)
+            Statement::Let {
+                pattern,
+                mutable,
+                value,
+                ..
+            } => {
+                let mutable = if *mutable { "mut " } else { "" };
+                write!(f, "let {mutable}{pattern} = {value};")
+            }
+            Statement::Return { value, .. } => write!(f, "return {value};"),
+            Statement::Function {
+                name,
+                parameters,
+                body,
+                ..
+            } => {
+                let parameters = parameters
+                    .iter()
+                    .map(|p| p.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                write!(f, "fn {name}({parameters}) {{ {body} }}")
             }
-            Statement::Return(value) => write!(f, "return {value};"),
-            Statement::Expression {
-                token: _,
-                expression,
-            } => write!(f, "{expression}"),
+            Statement::While {
+                condition, body, ..
+            } => write!(f, "while ({condition}) {{ {body} }}"),
+            Statement::For {
+                variable,
+                iterable,
+                body,
+                ..
+            } => write!(f, "for ({variable} in {iterable}) {{ {body} }}"),
+            Statement::Break { .. } => write!(f, "break;"),
+            Statement::Continue { .. } => write!(f, "continue;"),
+            Statement::Throw { value, .. } => write!(f, "throw {value};"),
+            Statement::Import { path, alias, .. } => {
+                write!(f, "import \"{}\" as {alias};", path.escape_default())
+            }
+            Statement::Export { statement, .. } => write!(f, "export {statement}"),
+            Statement::Expression { expression, .. } => write!(f, "{expression}"),
         }
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "Block")]
 pub struct BlockStatement {
     pub statements: Vec<Statement>,
+    /// From the opening `{` to the closing `}`.
+    pub span: Span,
 }
 
 impl Node for BlockStatement {
@@ -58,10 +164,24 @@ impl Node for BlockStatement {
 
 impl Display for BlockStatement {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for statement in &self.statements {
-            write!(f, "{}", statement)?;
-        }
+        write_statements(f, &self.statements)
+    }
+}
+
+/// Writes statements back to back, separating an expression statement from
+/// the next one with `;` so the output parses as the same statements.
+pub(super) fn write_statements(
+    f: &mut std::fmt::Formatter<'_>,
+    statements: &[Statement],
+) -> std::fmt::Result {
+    for (i, statement) in statements.iter().enumerate() {
+        write!(f, "{statement}")?;
 
-        Ok(())
+        let is_last = i + 1 == statements.len();
+        if !is_last && matches!(statement, Statement::Expression { .. }) {
+            write!(f, "; ")?;
+        }
     }
+
+    Ok(())
 }
@@ -0,0 +1,294 @@
+//! Read-only traversal of the syntax tree.
+//!
+//! Implement [`Visitor`] and override the methods for the nodes of interest.
+//! Each default method calls the matching `walk_*` function, which visits
+//! the node's children; an override calls it too to keep descending.
+
+use super::{
+    BlockStatement, Expression, Identifer, MatchArm, Parameter, Pattern, Program, Statement,
+};
+
+pub trait Visitor {
+    fn visit_program(&mut self, program: &Program) {
+        walk_program(self, program);
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) {
+        walk_statement(self, statement);
+    }
+
+    fn visit_block_statement(&mut self, block: &BlockStatement) {
+        walk_block_statement(self, block);
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        walk_expression(self, expression);
+    }
+
+    fn visit_match_arm(&mut self, arm: &MatchArm) {
+        walk_match_arm(self, arm);
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        walk_pattern(self, pattern);
+    }
+
+    fn visit_parameter(&mut self, parameter: &Parameter) {
+        walk_parameter(self, parameter);
+    }
+
+    /// Called for names that are declared: bindings in patterns, function
+    /// names, loop variables and import aliases. Names that are used are
+    /// `Expression::Identifier`s.
+    fn visit_identifier(&mut self, _identifier: &Identifer) {}
+}
+
+pub fn walk_program<V: Visitor + ?Sized>(visitor: &mut V, program: &Program) {
+    for statement in &program.statements {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_statement<V: Visitor + ?Sized>(visitor: &mut V, statement: &Statement) {
+    match statement {
+        Statement::Let { pattern, value, .. } => {
+            visitor.visit_pattern(pattern);
+            visitor.visit_expression(value);
+        }
+        Statement::Return { value, .. } | Statement::Throw { value, .. } => {
+            visitor.visit_expression(value)
+        }
+        Statement::Function {
+            name,
+            parameters,
+            body,
+            ..
+        } => {
+            visitor.visit_identifier(name);
+            for parameter in parameters {
+                visitor.visit_parameter(parameter);
+            }
+            visitor.visit_block_statement(body);
+        }
+        Statement::While {
+            condition, body, ..
+        } => {
+            visitor.visit_expression(condition);
+            visitor.visit_block_statement(body);
+        }
+        Statement::For {
+            variable,
+            iterable,
+            body,
+            ..
+        } => {
+            visitor.visit_identifier(variable);
+            visitor.visit_expression(iterable);
+            visitor.visit_block_statement(body);
+        }
+        Statement::Break { .. } | Statement::Continue { .. } => {}
+        Statement::Import { alias, .. } => visitor.visit_identifier(alias),
+        Statement::Export { statement, .. } => visitor.visit_statement(statement),
+        Statement::Expression { expression, .. } => visitor.visit_expression(expression),
+    }
+}
+
+pub fn walk_block_statement<V: Visitor + ?Sized>(visitor: &mut V, block: &BlockStatement) {
+    for statement in &block.statements {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &Expression) {
+    match expression {
+        Expression::Identifier { .. }
+        | Expression::IntegerLiteral { .. }
+        | Expression::FloatLiteral { .. }
+        | Expression::StringLiteral { .. }
+        | Expression::BooleanLiteral { .. } => {}
+        Expression::ArrayLiteral { elements, .. } => {
+            for element in elements {
+                visitor.visit_expression(element);
+            }
+        }
+        Expression::HashLiteral { pairs, .. } => {
+            for (key, value) in pairs {
+                visitor.visit_expression(key);
+                visitor.visit_expression(value);
+            }
+        }
+        Expression::Prefix { right, .. } => visitor.visit_expression(right),
+        Expression::Infix { left, right, .. } => {
+            visitor.visit_expression(left);
+            visitor.visit_expression(right);
+        }
+        Expression::If {
+            branches,
+            alternative,
+            ..
+        } => {
+            for (condition, consequence) in branches {
+                visitor.visit_expression(condition);
+                visitor.visit_block_statement(consequence);
+            }
+            if let Some(alternative) = alternative {
+                visitor.visit_block_statement(alternative);
+            }
+        }
+        Expression::Match { subject, arms, .. } => {
+            visitor.visit_expression(subject);
+            for arm in arms {
+                visitor.visit_match_arm(arm);
+            }
+        }
+        Expression::Try {
+            body,
+            catch,
+            finally,
+            ..
+        } => {
+            visitor.visit_block_statement(body);
+            if let Some((pattern, handler)) = catch.as_deref() {
+                visitor.visit_pattern(pattern);
+                visitor.visit_block_statement(handler);
+            }
+            if let Some(cleanup) = finally {
+                visitor.visit_block_statement(cleanup);
+            }
+        }
+        Expression::Conditional {
+            condition,
+            consequence,
+            alternative,
+            ..
+        } => {
+            visitor.visit_expression(condition);
+            visitor.visit_expression(consequence);
+            visitor.visit_expression(alternative);
+        }
+        Expression::FunctionLiteral {
+            parameters, body, ..
+        } => {
+            for parameter in parameters {
+                visitor.visit_parameter(parameter);
+            }
+            visitor.visit_block_statement(body);
+        }
+        Expression::Call {
+            function,
+            arguments,
+            ..
+        } => {
+            visitor.visit_expression(function);
+            for argument in arguments {
+                visitor.visit_expression(argument);
+            }
+        }
+        Expression::Spread { value, .. } => visitor.visit_expression(value),
+        Expression::Index { left, index, .. } => {
+            visitor.visit_expression(left);
+            visitor.visit_expression(index);
+        }
+        Expression::Member { object, .. } => visitor.visit_expression(object),
+        Expression::Range { start, end, .. } => {
+            visitor.visit_expression(start);
+            visitor.visit_expression(end);
+        }
+        Expression::Assign { target, value, .. } => {
+            visitor.visit_expression(target);
+            visitor.visit_expression(value);
+        }
+    }
+}
+
+pub fn walk_match_arm<V: Visitor + ?Sized>(visitor: &mut V, arm: &MatchArm) {
+    visitor.visit_pattern(&arm.pattern);
+    if let Some(guard) = &arm.guard {
+        visitor.visit_expression(guard);
+    }
+    visitor.visit_block_statement(&arm.body);
+}
+
+pub fn walk_pattern<V: Visitor + ?Sized>(visitor: &mut V, pattern: &Pattern) {
+    match pattern {
+        Pattern::Wildcard => {}
+        Pattern::Literal(literal) => visitor.visit_expression(literal),
+        Pattern::Binding(name) => visitor.visit_identifier(name),
+        Pattern::Array { elements, rest } => {
+            for element in elements {
+                visitor.visit_pattern(element);
+            }
+            if let Some(rest) = rest {
+                visitor.visit_pattern(rest);
+            }
+        }
+        Pattern::Hash(pairs) => {
+            for (key, pattern) in pairs {
+                visitor.visit_expression(key);
+                visitor.visit_pattern(pattern);
+            }
+        }
+    }
+}
+
+pub fn walk_parameter<V: Visitor + ?Sized>(visitor: &mut V, parameter: &Parameter) {
+    visitor.visit_pattern(&parameter.pattern);
+    if let Some(default) = &parameter.default {
+        visitor.visit_expression(default);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::ast::{parse, Expression, Identifer};
+
+    use super::{walk_expression, Visitor};
+
+    /// Collects declared names and the names of called functions.
+    #[derive(Default)]
+    struct Names {
+        declared: Vec<String>,
+        called: Vec<String>,
+    }
+
+    impl Visitor for Names {
+        fn visit_expression(&mut self, expression: &Expression) {
+            if let Expression::Call { function, .. } = expression {
+                self.called.push(function.to_string());
+            }
+            walk_expression(self, expression);
+        }
+
+        fn visit_identifier(&mut self, identifier: &Identifer) {
+            self.declared.push(identifier.value.clone());
+        }
+    }
+
+    #[test]
+    fn visitor_reaches_every_node() {
+        // Arrange
+        let program = parse(
+            r#"
+            import "lib.gb" as lib;
+            export fn f([a, ...rest], b = g()) { for (x in rest) { h(x) } }
+            let {name} = match (a) { [n] if check(n) => n, _ => try { i() } catch (e) { j(e) } finally { k() } };
+            while (l()) { throw m(); }
+            let p = fn(q) { n(...q)[o()].z ? r() : s() };
+            "#,
+        );
+        let mut names = Names::default();
+
+        // Act
+        names.visit_program(&program);
+
+        // Assert
+        assert_eq!(
+            names.declared,
+            ["lib", "f", "a", "rest", "b", "x", "name", "n", "e", "p", "q"]
+        );
+        assert_eq!(
+            names.called,
+            ["g", "h", "check", "i", "j", "k", "l", "m", "n", "o", "r", "s"]
+        );
+    }
+}
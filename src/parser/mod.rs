@@ -1,116 +1,420 @@
-mod ast;
+pub mod ast;
 
-use crate::lexer::Lexer;
-use crate::token::{Kind, Token};
+use std::collections::HashMap;
 
-use ast::{BlockStatement, Expression, Identifer, Program, Statement};
+use crate::lexer::{parse_float, parse_integer, parse_negative_integer, unescape, Lexer};
+use crate::token::{Kind, Position, Reason, Span, Token};
+
+use ast::{
+    BlockStatement, Expression, Identifer, MatchArm, Parameter, Pattern, Program, Statement,
+};
 
 const LOWEST: i32 = 0;
-const EQUALITY: i32 = 1;
-const LESS_GREATER: i32 = 2;
-const SUM: i32 = 3;
-const PRODUCT: i32 = 4;
-const PREFIX: i32 = 5;
-const CALL: i32 = 6;
-
-pub struct Parser {
-    lexer: Lexer,
+const ASSIGN: i32 = 1;
+const CONDITIONAL: i32 = 2;
+const OR: i32 = 3;
+const AND: i32 = 4;
+const EQUALITY: i32 = 5;
+const LESS_GREATER: i32 = 6;
+const RANGE: i32 = 7;
+const SUM: i32 = 8;
+const PRODUCT: i32 = 9;
+const PREFIX: i32 = 10;
+const CALL: i32 = 11;
+const INDEX: i32 = 12;
+
+/// How deeply expressions, blocks and patterns may nest in each other.
+const MAX_NESTING: usize = 1000;
+
+/// Parsing that starts with less stack than this left moves to a new
+/// segment of `STACK_SEGMENT` bytes.
+const STACK_RED_ZONE: usize = 256 * 1024;
+const STACK_SEGMENT: usize = 4 * 1024 * 1024;
+
+pub struct Parser<'a> {
+    lexer: Lexer<'a>,
     pub errors: Vec<String>,
-    current_token: Token,
-    peek_token: Token,
+    /// Problems that do not stop the program from running.
+    pub warnings: Vec<String>,
+    current_token: Token<'a>,
+    peek_token: Token<'a>,
+    /// Number of loops enclosing the current token within its function.
+    loop_depth: usize,
+    /// Number of expressions, blocks and patterns enclosing the current token.
+    nesting: usize,
+    /// Number of `{` before the current token that are not closed yet.
+    braces: usize,
+    /// Names declared so far, innermost scope last. Only functions and
+    /// match arms open a new scope; blocks share the scope around them.
+    scopes: Vec<HashMap<String, Binding>>,
+}
+
+/// Where a name was declared and whether it may be assigned to.
+struct Binding {
+    position: Position,
+    mutable: bool,
 }
 
-impl Parser {
-    pub fn new(mut lexer: Lexer) -> Self {
+impl<'a> Parser<'a> {
+    pub fn new(mut lexer: Lexer<'a>) -> Self {
         let current_token = lexer.next_token();
         let peek_token = lexer.next_token();
 
         Parser {
             lexer,
             errors: vec![],
+            warnings: vec![],
             current_token,
             peek_token,
+            loop_depth: 0,
+            nesting: 0,
+            braces: 0,
+            scopes: vec![HashMap::new()],
         }
     }
 
     fn next_token(&mut self) {
-        self.current_token = self.peek_token.clone();
+        self.braces = self.braces_after_current();
+        self.current_token = self.peek_token;
         self.peek_token = self.lexer.next_token();
     }
 
     pub fn parse_program(&mut self) -> Program {
-        let mut program = Program { statements: vec![] };
+        let mut statements = vec![];
 
         while self.current_token.kind != Kind::Eof {
-            program.statements.push(self.parse_statement());
+            let depth = self.braces;
+            let statement = if self.current_token.kind == Kind::Export {
+                self.parse_export_statement()
+            } else {
+                self.parse_statement()
+            };
+            match statement {
+                Some(statement) => statements.push(statement),
+                None => self.skip_statement(depth),
+            }
             self.next_token();
         }
 
-        program
+        Program {
+            statements,
+            span: Span::new(0, self.current_token.span.end),
+        }
+    }
+
+    fn braces_after_current(&self) -> usize {
+        match self.current_token.kind {
+            Kind::LBrace => self.braces + 1,
+            Kind::RBrace => self.braces.saturating_sub(1),
+            _ => self.braces,
+        }
+    }
+
+    /// Skips the rest of a statement that failed to parse, where `depth` is
+    /// the number of braces the statement is in. Stops on its `;` or before
+    /// the `}` closing its block, so the rest of the statement is not
+    /// reported as further errors.
+    fn skip_statement(&mut self, depth: usize) {
+        loop {
+            match self.current_token.kind {
+                Kind::Eof => return,
+                Kind::SemiColon if self.braces == depth => return,
+                _ => {}
+            }
+            if self.braces_after_current() == depth && self.peek_token.kind == Kind::RBrace {
+                return;
+            }
+            self.next_token();
+        }
     }
 
-    fn parse_statement(&mut self) -> Statement {
+    fn parse_statement(&mut self) -> Option<Statement> {
+        let start = self.current_token;
         match &self.current_token.kind {
             Kind::Let => {
-                if !self.expect_peek(Kind::Ident) {
-                    panic!("let must be follow by an identifier")
+                let mutable = self.peek_token.kind == Kind::Mut;
+                if mutable {
+                    self.next_token();
                 }
 
-                let name = Identifer {
-                    token: self.current_token.clone(),
-                    value: self.current_token.literal.clone(),
-                };
+                self.next_token();
+                let pattern = self.parse_pattern()?;
 
                 if !self.expect_peek(Kind::Assign) {
-                    panic!("let identifier must be follow by an assign token")
+                    return None;
                 }
 
                 self.next_token();
 
-                let value = self.parse_expression(LOWEST);
+                let mut value = self.parse_expression(LOWEST)?;
 
                 if self.peek_token.kind == Kind::SemiColon {
                     self.next_token();
                 }
 
-                Statement::Let { name, value }
+                if let (
+                    Pattern::Binding(name),
+                    Expression::FunctionLiteral { name: function, .. },
+                ) = (&pattern, &mut value)
+                {
+                    function.get_or_insert_with(|| name.value.clone());
+                }
+
+                self.declare_pattern(&pattern, mutable);
+
+                Some(Statement::Let {
+                    pattern,
+                    mutable,
+                    value,
+                    span: self.span_from(start),
+                })
             }
-            Kind::Return => {
+            Kind::Return | Kind::Throw => {
+                let kind = self.current_token.kind;
                 self.next_token();
 
-                let value = self.parse_expression(LOWEST);
+                let value = self.parse_expression(LOWEST)?;
+
+                if self.peek_token.kind == Kind::SemiColon {
+                    self.next_token();
+                }
+
+                let span = self.span_from(start);
+                if kind == Kind::Return {
+                    Some(Statement::Return { value, span })
+                } else {
+                    Some(Statement::Throw { value, span })
+                }
+            }
+            Kind::Function if self.peek_token.kind == Kind::Ident => {
+                self.parse_function_declaration()
+            }
+            Kind::While => self.parse_while_statement(),
+            Kind::For => self.parse_for_statement(),
+            Kind::Import => self.parse_import_statement(),
+            Kind::Export => {
+                self.error(
+                    self.current_token,
+                    "export is only allowed at the top level of a module".to_string(),
+                );
+                None
+            }
+            Kind::Break | Kind::Continue => {
+                let token = self.current_token;
 
                 if self.peek_token.kind == Kind::SemiColon {
                     self.next_token();
                 }
 
-                Statement::Return(value)
+                if self.loop_depth == 0 {
+                    self.error(token, format!("{} outside of a loop", token.literal));
+                    return None;
+                }
+
+                let span = self.span_from(token);
+                if token.kind == Kind::Break {
+                    Some(Statement::Break { span })
+                } else {
+                    Some(Statement::Continue { span })
+                }
             }
             _ => {
-                let statement = Statement::Expression {
-                    token: self.current_token.clone(),
-                    expression: self.parse_expression(LOWEST),
-                };
+                let expression = self.parse_expression(LOWEST)?;
 
                 if self.peek_token.kind == Kind::SemiColon {
                     self.next_token();
                 }
 
-                statement
+                Some(Statement::Expression {
+                    span: self.span_from(start),
+                    expression,
+                })
             }
         }
     }
 
-    fn parse_expression(&mut self, precendence: i32) -> Expression {
+    fn parse_function_declaration(&mut self) -> Option<Statement> {
+        let start = self.current_token;
+        self.next_token();
+        let name = self.current_identifier();
+
+        // Declared before the body is parsed so the body can refer to it.
+        self.declare(&name, false);
+
+        let Expression::FunctionLiteral {
+            parameters, body, ..
+        } = self.parse_function_literal()?
+        else {
+            unreachable!("parse_function_literal only returns function literals");
+        };
+
+        if self.peek_token.kind == Kind::SemiColon {
+            self.next_token();
+        }
+
+        Some(Statement::Function {
+            name,
+            parameters,
+            body,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_import_statement(&mut self) -> Option<Statement> {
+        let start = self.current_token;
+        if !self.expect_peek(Kind::Str) {
+            return None;
+        }
+        let path = self.current_token.literal.to_string();
+
+        if !self.expect_peek(Kind::As) || !self.expect_peek(Kind::Ident) {
+            return None;
+        }
+        let alias = self.current_identifier();
+        self.declare(&alias, false);
+
+        if self.peek_token.kind == Kind::SemiColon {
+            self.next_token();
+        }
+
+        Some(Statement::Import {
+            path,
+            alias,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Only `let` and named `fn` declarations can be exported.
+    fn parse_export_statement(&mut self) -> Option<Statement> {
+        let token = self.current_token;
+        self.next_token();
+
+        let statement = self.parse_statement()?;
+        if !matches!(
+            statement,
+            Statement::Let { .. } | Statement::Function { .. }
+        ) {
+            self.error(
+                token,
+                "export must be followed by a let or fn declaration".to_string(),
+            );
+            return None;
+        }
+
+        Some(Statement::Export {
+            statement: Box::new(statement),
+            span: self.span_from(token),
+        })
+    }
+
+    fn parse_while_statement(&mut self) -> Option<Statement> {
+        let start = self.current_token;
+        if !self.expect_peek(Kind::LParen) {
+            return None;
+        }
+
+        self.next_token();
+        let condition = self.parse_expression(LOWEST)?;
+
+        if !self.expect_peek(Kind::RParen) || !self.expect_peek(Kind::LBrace) {
+            return None;
+        }
+
+        self.loop_depth += 1;
+        let body = self.parse_block_statement();
+        self.loop_depth -= 1;
+
+        if self.peek_token.kind == Kind::SemiColon {
+            self.next_token();
+        }
+
+        Some(Statement::While {
+            condition,
+            body,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_for_statement(&mut self) -> Option<Statement> {
+        let start = self.current_token;
+        if !self.expect_peek(Kind::LParen) || !self.expect_peek(Kind::Ident) {
+            return None;
+        }
+
+        let variable = self.current_identifier();
+
+        if !self.expect_peek(Kind::In) {
+            return None;
+        }
+
+        self.next_token();
+        let iterable = self.parse_expression(LOWEST)?;
+
+        if !self.expect_peek(Kind::RParen) || !self.expect_peek(Kind::LBrace) {
+            return None;
+        }
+
+        // The variable is only bound inside the body.
+        self.scopes.push(HashMap::new());
+        self.declare(&variable, false);
+        self.loop_depth += 1;
+        let body = self.parse_block_statement();
+        self.loop_depth -= 1;
+        self.scopes.pop();
+
+        if self.peek_token.kind == Kind::SemiColon {
+            self.next_token();
+        }
+
+        Some(Statement::For {
+            variable,
+            iterable,
+            body,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_expression(&mut self, precendence: i32) -> Option<Expression> {
+        self.nested(|parser| parser.parse_expression_body(precendence))
+    }
+
+    fn parse_expression_body(&mut self, precendence: i32) -> Option<Expression> {
+        let start = self.current_token;
         let mut left = match &self.current_token.kind {
             Kind::Ident => self.parse_identifier(),
-            Kind::Int => self.parse_integer_literal(),
+            Kind::Int => self.parse_integer_literal()?,
+            Kind::Float => self.parse_float_literal()?,
+            Kind::Str => self.parse_string_literal(),
             Kind::True | Kind::False => self.parse_boolean_literal(),
-            Kind::Bang | Kind::Minus => self.parse_prefix(),
-            Kind::LParen => self.parse_grouped_expression(),
-            Kind::If => self.parse_if_expression(),
-            Kind::Function => self.parse_function_literal(),
-            token => panic!("can't parse expression that starts with {token:?}"),
+            Kind::Bang | Kind::Minus => self.parse_prefix()?,
+            Kind::LParen => self.parse_grouped_expression()?,
+            Kind::LBracket => Expression::ArrayLiteral {
+                elements: self.parse_expression_list(Kind::RBracket)?,
+                span: self.span_from(start),
+            },
+            Kind::LBrace => self.parse_hash_literal()?,
+            Kind::If => self.parse_if_expression()?,
+            Kind::Match => self.parse_match_expression()?,
+            Kind::Try => self.parse_try_expression()?,
+            Kind::Function => self.parse_function_literal()?,
+            Kind::DotDotDot => {
+                self.error(
+                    self.current_token,
+                    "spread is only allowed in call arguments".to_string(),
+                );
+                return None;
+            }
+            Kind::Illegal(reason) => {
+                self.illegal_token_error(self.current_token, *reason);
+                return None;
+            }
+            token => {
+                self.error(
+                    self.current_token,
+                    format!("can't parse expression that starts with {token:?}"),
+                );
+                return None;
+            }
         };
 
         while self.peek_token.kind != Kind::SemiColon && precendence < self.peek_precedence() {
@@ -119,234 +423,934 @@ impl Parser {
                 | Kind::Minus
                 | Kind::Asterix
                 | Kind::Slash
+                | Kind::Percent
                 | Kind::Eq
                 | Kind::Ne
                 | Kind::Gt
-                | Kind::Lt => {
+                | Kind::Lt
+                | Kind::GtEq
+                | Kind::LtEq
+                | Kind::And
+                | Kind::Or => {
                     self.next_token();
-                    self.parse_infix(left)
+                    self.parse_infix(left, start)?
                 }
                 Kind::LParen => {
                     self.next_token();
-                    self.parse_call_expression(left)
+                    self.parse_call_expression(left, start)?
                 }
-                _ => return left,
+                Kind::LBracket => {
+                    self.next_token();
+                    self.parse_index_expression(left, start)?
+                }
+                Kind::Dot => {
+                    self.next_token();
+                    self.parse_member_expression(left, start)?
+                }
+                Kind::DotDot | Kind::DotDotEq => {
+                    self.next_token();
+                    self.parse_range_expression(left, start)?
+                }
+                Kind::Question => {
+                    self.next_token();
+                    self.parse_conditional_expression(left, start)?
+                }
+                Kind::Assign
+                | Kind::PlusAssign
+                | Kind::MinusAssign
+                | Kind::AsterixAssign
+                | Kind::SlashAssign => {
+                    self.next_token();
+                    self.parse_assign_expression(left, start)?
+                }
+                _ => return Some(left),
             }
         }
 
-        left
+        Some(left)
+    }
+
+    /// The span from the start of `start` to the end of the current token.
+    fn span_from(&self, start: Token) -> Span {
+        start.span.to(self.current_token.span)
     }
 
     fn parse_identifier(&self) -> Expression {
-        Expression::Identifier(self.current_token.literal.clone())
+        Expression::Identifier {
+            name: self.current_token.literal.to_string(),
+            span: self.current_token.span,
+        }
+    }
+
+    fn parse_integer_literal(&mut self) -> Option<Expression> {
+        match parse_integer(self.current_token.literal) {
+            Ok(value) => Some(Expression::IntegerLiteral {
+                value,
+                span: self.current_token.span,
+            }),
+            Err(_) => {
+                self.illegal_token_error(self.current_token, Reason::IntegerOverflow);
+                None
+            }
+        }
+    }
+
+    fn parse_float_literal(&mut self) -> Option<Expression> {
+        match parse_float(self.current_token.literal) {
+            Ok(value) => Some(Expression::FloatLiteral {
+                value,
+                span: self.current_token.span,
+            }),
+            Err(error) => {
+                self.error(
+                    self.current_token,
+                    format!("invalid float literal: {error}"),
+                );
+                None
+            }
+        }
     }
 
-    fn parse_integer_literal(&mut self) -> Expression {
-        Expression::IntegerLiteral(
-            self.current_token
-                .literal
-                .parse::<i64>()
-                .expect("could not parse integer literal"),
-        )
+    fn parse_string_literal(&self) -> Expression {
+        Expression::StringLiteral {
+            value: unescape(self.current_token.literal),
+            span: self.current_token.span,
+        }
     }
 
     fn parse_boolean_literal(&mut self) -> Expression {
-        Expression::BooleanLiteral(self.current_token.kind == Kind::True)
+        Expression::BooleanLiteral {
+            value: self.current_token.kind == Kind::True,
+            span: self.current_token.span,
+        }
+    }
+
+    fn parse_prefix(&mut self) -> Option<Expression> {
+        if let Some(literal) = self.parse_negated_integer_literal() {
+            if self.peek_precedence() > PREFIX {
+                self.illegal_token_error(self.current_token, Reason::IntegerOverflow);
+                return None;
+            }
+            return Some(literal);
+        }
+
+        let start = self.current_token;
+        let operator = self.current_token.literal.to_string();
+        self.next_token();
+        let right = Box::new(self.parse_expression(PREFIX)?);
+        Some(Expression::Prefix {
+            operator,
+            right,
+            span: self.span_from(start),
+        })
     }
 
-    fn parse_prefix(&mut self) -> Expression {
-        let operator = self.current_token.literal.clone();
+    /// Folds a `-` directly followed by an integer literal too large on its
+    /// own into one literal, so that `-9223372036854775808` can be written.
+    /// Returns `None`, without moving, for any other `-`.
+    fn parse_negated_integer_literal(&mut self) -> Option<Expression> {
+        let start = self.current_token;
+        let digits = self.peek_token;
+        if start.kind != Kind::Minus
+            || digits.kind != Kind::Illegal(Reason::IntegerOverflow)
+            || digits.span.start != start.span.end
+        {
+            return None;
+        }
+
+        let value = parse_negative_integer(digits.literal).ok()?;
         self.next_token();
-        let right = Box::new(self.parse_expression(PREFIX));
-        Expression::Prefix { operator, right }
+        Some(Expression::IntegerLiteral {
+            value,
+            span: self.span_from(start),
+        })
     }
 
-    fn parse_infix(&mut self, left: Expression) -> Expression {
+    /// `start` is the first token of `left`, as for the other operators
+    /// that follow their first operand.
+    fn parse_infix(&mut self, left: Expression, start: Token) -> Option<Expression> {
         let left = Box::new(left);
-        let operator = self.current_token.literal.clone();
+        let operator = self.current_token.literal.to_string();
 
         let precedence = self.current_precedence();
         self.next_token();
-        let right = Box::new(self.parse_expression(precedence));
+        let right = Box::new(self.parse_expression(precedence)?);
 
-        Expression::Infix {
+        Some(Expression::Infix {
             left,
             operator,
             right,
-        }
+            span: self.span_from(start),
+        })
     }
 
-    fn parse_grouped_expression(&mut self) -> Expression {
-        self.next_token();
+    fn parse_range_expression(&mut self, left: Expression, start: Token) -> Option<Expression> {
+        let inclusive = self.current_token.kind == Kind::DotDotEq;
 
-        let expression = self.parse_expression(LOWEST);
+        self.next_token();
+        let end = self.parse_expression(RANGE)?;
+
+        Some(Expression::Range {
+            start: Box::new(left),
+            end: Box::new(end),
+            inclusive,
+            span: self.span_from(start),
+        })
+    }
 
-        if !self.expect_peek(Kind::RParen) {
-            panic!("expected right paren")
-        } else {
-            expression
+    /// Assignment is right-associative, so the value is parsed with a
+    /// precedence just below `ASSIGN`: `a = b = c` is `a = (b = c)`.
+    /// `start` is the first token of the target.
+    fn parse_assign_expression(&mut self, target: Expression, start: Token) -> Option<Expression> {
+        let mut root = &target;
+        while let Expression::Index { left, .. } = root {
+            root = left;
         }
-    }
 
-    fn parse_if_expression(&mut self) -> Expression {
-        if !self.expect_peek(Kind::LParen) {
-            panic!("expected left paren")
+        let Expression::Identifier { name, .. } = root else {
+            self.error(start, format!("invalid assignment target: {target}"));
+            return None;
+        };
+
+        if let Some(binding) = self.lookup(name).filter(|binding| !binding.mutable) {
+            let message = format!(
+                "cannot assign to immutable binding {name} (declared at {}, use `let mut {name}`)",
+                binding.position
+            );
+            self.error(start, message);
+            return None;
         }
 
+        let operator = self.current_token.literal.to_string();
+
         self.next_token();
-        let condition = self.parse_expression(LOWEST);
+        let value = self.parse_expression(ASSIGN - 1)?;
 
-        if !self.expect_peek(Kind::RParen) || !self.expect_peek(Kind::LBrace) {
-            panic!("expected right paren or left brace")
-        }
+        Some(Expression::Assign {
+            target: Box::new(target),
+            operator,
+            value: Box::new(value),
+            span: self.span_from(start),
+        })
+    }
 
-        let consequence = self.parse_block_statement();
+    fn parse_grouped_expression(&mut self) -> Option<Expression> {
+        self.next_token();
 
-        if self.peek_token.kind == Kind::Else {
-            self.next_token();
+        let expression = self.parse_expression(LOWEST)?;
 
-            if !self.expect_peek(Kind::LBrace) {
-                panic!("expected left brace")
-            }
+        if !self.expect_peek(Kind::RParen) {
+            None
+        } else {
+            Some(expression)
+        }
+    }
 
-            let alternative = self.parse_block_statement();
-            return Expression::If {
-                condition: Box::new(condition),
-                consequence: Box::new(consequence),
-                alternative: Some(Box::new(alternative)),
-            };
+    /// The consequence may be any expression, while the alternative is
+    /// parsed just below `CONDITIONAL` so `a ? b : c ? d : e` nests to the
+    /// right.
+    fn parse_conditional_expression(
+        &mut self,
+        condition: Expression,
+        start: Token,
+    ) -> Option<Expression> {
+        self.next_token();
+        let consequence = self.parse_expression(LOWEST)?;
+
+        if !self.expect_peek(Kind::Colon) {
+            return None;
         }
 
-        Expression::If {
+        self.next_token();
+        let alternative = self.parse_expression(CONDITIONAL - 1)?;
+
+        Some(Expression::Conditional {
             condition: Box::new(condition),
             consequence: Box::new(consequence),
-            alternative: None,
-        }
+            alternative: Box::new(alternative),
+            span: self.span_from(start),
+        })
     }
 
-    fn parse_function_literal(&mut self) -> Expression {
+    fn parse_match_expression(&mut self) -> Option<Expression> {
+        let token = self.current_token;
+
         if !self.expect_peek(Kind::LParen) {
-            panic!("Expected left paren")
+            return None;
         }
 
-        let parameters = self.parse_function_parameters();
+        self.next_token();
+        let subject = self.parse_expression(LOWEST)?;
 
-        if !self.expect_peek(Kind::LBrace) {
-            panic!("Expected left brace")
+        if !self.expect_peek(Kind::RParen) || !self.expect_peek(Kind::LBrace) {
+            return None;
         }
 
-        let body = self.parse_block_statement();
+        let mut arms = vec![];
+        while self.peek_token.kind != Kind::RBrace {
+            self.next_token();
 
-        Expression::FunctionLiteral {
-            parameters: parameters.unwrap(),
-            body,
-        }
-    }
+            self.scopes.push(HashMap::new());
+            let arm = self.parse_match_arm();
+            self.scopes.pop();
+            arms.push(arm?);
 
-    fn parse_block_statement(&mut self) -> BlockStatement {
-        let mut statements = vec![];
+            if self.peek_token.kind != Kind::RBrace && !self.expect_peek(Kind::Comma) {
+                return None;
+            }
+        }
         self.next_token();
 
-        while self.current_token.kind != Kind::RBrace && self.current_token.kind != Kind::Eof {
-            let statement = self.parse_statement();
-            statements.push(statement);
-            self.next_token();
+        if !arms.iter().any(MatchArm::is_catch_all) {
+            self.warning(
+                token,
+                "non-exhaustive match: add a `_` arm to handle any other value".to_string(),
+            );
         }
 
-        BlockStatement { statements }
+        Some(Expression::Match {
+            subject: Box::new(subject),
+            arms,
+            span: self.span_from(token),
+        })
     }
 
-    fn parse_function_parameters(&mut self) -> Option<Vec<Identifer>> {
-        let mut identifiers = vec![];
+    /// An arm's body is either a block or a single expression, which is
+    /// stored as a block of one statement.
+    fn parse_match_arm(&mut self) -> Option<MatchArm> {
+        let pattern = self.parse_pattern()?;
+        self.declare_pattern(&pattern, false);
 
-        if self.peek_token.kind == Kind::RParen {
+        let mut guard = None;
+        if self.peek_token.kind == Kind::If {
             self.next_token();
-            return Some(identifiers);
+            self.next_token();
+            guard = Some(self.parse_expression(LOWEST)?);
         }
 
-        self.next_token();
-
-        let identifier = Identifer {
-            token: self.current_token.clone(),
-            value: self.current_token.literal.clone(),
-        };
-        identifiers.push(identifier);
+        if !self.expect_peek(Kind::FatArrow) {
+            return None;
+        }
 
-        while self.peek_token.kind == Kind::Comma {
+        let body = if self.peek_token.kind == Kind::LBrace {
             self.next_token();
+            self.parse_block_statement()
+        } else {
             self.next_token();
-            let identifier = Identifer {
-                token: self.current_token.clone(),
-                value: self.current_token.literal.clone(),
-            };
-            identifiers.push(identifier);
-        }
+            let expression = self.parse_expression(LOWEST)?;
+            let span = expression.span();
+            BlockStatement {
+                statements: vec![Statement::Expression { span, expression }],
+                span,
+            }
+        };
 
-        if !self.expect_peek(Kind::RParen) {
-            return None;
+        Some(MatchArm {
+            pattern,
+            guard,
+            body,
+        })
+    }
+
+    /// Parses the pattern starting at the current token. The names it binds
+    /// are declared separately, once it is known where they are visible.
+    fn parse_pattern(&mut self) -> Option<Pattern> {
+        self.nested(Self::parse_pattern_body)
+    }
+
+    fn parse_pattern_body(&mut self) -> Option<Pattern> {
+        if let Some(literal) = self.parse_negated_integer_literal() {
+            return Some(Pattern::Literal(literal));
         }
 
-        Some(identifiers)
+        match self.current_token.kind {
+            Kind::Ident if self.current_token.literal == "_" => Some(Pattern::Wildcard),
+            Kind::Ident => Some(Pattern::Binding(self.current_identifier())),
+            Kind::Minus if matches!(self.peek_token.kind, Kind::Int | Kind::Float) => {
+                let start = self.current_token;
+                let operator = self.current_token.literal.to_string();
+                self.next_token();
+                let right = Box::new(self.parse_literal()?);
+                Some(Pattern::Literal(Expression::Prefix {
+                    operator,
+                    right,
+                    span: self.span_from(start),
+                }))
+            }
+            Kind::Int | Kind::Float | Kind::Str | Kind::True | Kind::False => {
+                Some(Pattern::Literal(self.parse_literal()?))
+            }
+            Kind::LBracket => self.parse_array_pattern(),
+            Kind::LBrace => self.parse_hash_pattern(),
+            Kind::Illegal(reason) => {
+                self.illegal_token_error(self.current_token, reason);
+                None
+            }
+            kind => {
+                self.error(
+                    self.current_token,
+                    format!("expected a pattern, got {kind:?} instead"),
+                );
+                None
+            }
+        }
     }
 
-    fn parse_call_expression(&mut self, function: Expression) -> Expression {
-        Expression::Call {
-            function: Box::new(function),
-            arguments: self.parse_call_arguments().unwrap(),
+    fn parse_literal(&mut self) -> Option<Expression> {
+        match self.current_token.kind {
+            Kind::Int => self.parse_integer_literal(),
+            Kind::Float => self.parse_float_literal(),
+            Kind::Str => Some(self.parse_string_literal()),
+            Kind::True | Kind::False => Some(self.parse_boolean_literal()),
+            kind => {
+                self.error(
+                    self.current_token,
+                    format!("expected a literal, got {kind:?} instead"),
+                );
+                None
+            }
         }
     }
 
-    fn parse_call_arguments(&mut self) -> Option<Vec<Expression>> {
-        let mut args = vec![];
+    fn parse_array_pattern(&mut self) -> Option<Pattern> {
+        let mut elements = vec![];
+        let mut rest = None;
+
+        while self.peek_token.kind != Kind::RBracket {
+            if rest.is_some() {
+                self.error(
+                    self.peek_token,
+                    "rest pattern must be the last element".to_string(),
+                );
+                return None;
+            }
 
-        if self.peek_token.kind == Kind::RParen {
             self.next_token();
-            return Some(args);
-        }
+            if self.current_token.kind == Kind::DotDotDot {
+                if !self.expect_peek(Kind::Ident) {
+                    return None;
+                }
+                rest = Some(Box::new(self.parse_pattern()?));
+            } else {
+                elements.push(self.parse_pattern()?);
+            }
 
+            if self.peek_token.kind != Kind::RBracket && !self.expect_peek(Kind::Comma) {
+                return None;
+            }
+        }
         self.next_token();
-        args.push(self.parse_expression(LOWEST));
 
-        while self.peek_token.kind == Kind::Comma {
-            self.next_token();
+        Some(Pattern::Array { elements, rest })
+    }
+
+    fn parse_hash_pattern(&mut self) -> Option<Pattern> {
+        let mut pairs = vec![];
+
+        while self.peek_token.kind != Kind::RBrace {
             self.next_token();
-            args.push(self.parse_expression(LOWEST));
-        }
 
-        if !self.expect_peek(Kind::RParen) {
-            return None;
+            // `{name}` is short for `{"name": name}`.
+            if self.current_token.kind == Kind::Ident && self.peek_token.kind != Kind::Colon {
+                let key = Expression::StringLiteral {
+                    value: self.current_token.literal.to_string(),
+                    span: self.current_token.span,
+                };
+                pairs.push((key, self.parse_pattern()?));
+            } else {
+                let key = self.parse_literal()?;
+
+                if !self.expect_peek(Kind::Colon) {
+                    return None;
+                }
+
+                self.next_token();
+                pairs.push((key, self.parse_pattern()?));
+            }
+
+            if self.peek_token.kind != Kind::RBrace && !self.expect_peek(Kind::Comma) {
+                return None;
+            }
         }
+        self.next_token();
 
-        Some(args)
+        Some(Pattern::Hash(pairs))
     }
 
-    fn peek_precedence(&self) -> i32 {
-        match self.peek_token.kind {
-            Kind::Eq | Kind::Ne => EQUALITY,
-            Kind::Lt | Kind::Gt => LESS_GREATER,
+    /// Parses `try` with a `catch` block, a `finally` block, or both.
+    fn parse_try_expression(&mut self) -> Option<Expression> {
+        let token = self.current_token;
+
+        if !self.expect_peek(Kind::LBrace) {
+            return None;
+        }
+        let body = self.parse_block_statement();
+
+        let mut catch = None;
+        if self.peek_token.kind == Kind::Catch {
+            self.next_token();
+            if !self.expect_peek(Kind::LParen) {
+                return None;
+            }
+            self.next_token();
+            let error = self.parse_pattern()?;
+
+            if !self.expect_peek(Kind::RParen) || !self.expect_peek(Kind::LBrace) {
+                return None;
+            }
+
+            // The error is only visible in the catch block.
+            self.scopes.push(HashMap::new());
+            self.declare_pattern(&error, false);
+            let handler = self.parse_block_statement();
+            self.scopes.pop();
+
+            catch = Some(Box::new((error, handler)));
+        }
+
+        let mut finally = None;
+        if self.peek_token.kind == Kind::Finally {
+            self.next_token();
+            if !self.expect_peek(Kind::LBrace) {
+                return None;
+            }
+            finally = Some(self.parse_block_statement());
+        }
+
+        if catch.is_none() && finally.is_none() {
+            self.error(token, "try needs a catch or finally block".to_string());
+            return None;
+        }
+
+        Some(Expression::Try {
+            body,
+            catch,
+            finally,
+            span: self.span_from(token),
+        })
+    }
+
+    /// Parses `if`, any number of `else if` branches and an optional final
+    /// `else` into a single chain.
+    fn parse_if_expression(&mut self) -> Option<Expression> {
+        let start = self.current_token;
+        let mut branches = vec![];
+
+        loop {
+            if !self.expect_peek(Kind::LParen) {
+                return None;
+            }
+
+            self.next_token();
+            let condition = self.parse_expression(LOWEST)?;
+
+            if !self.expect_peek(Kind::RParen) || !self.expect_peek(Kind::LBrace) {
+                return None;
+            }
+
+            branches.push((condition, self.parse_block_statement()));
+
+            if self.peek_token.kind != Kind::Else {
+                return Some(Expression::If {
+                    branches,
+                    alternative: None,
+                    span: self.span_from(start),
+                });
+            }
+            self.next_token();
+
+            if self.peek_token.kind != Kind::If {
+                break;
+            }
+            self.next_token();
+        }
+
+        if !self.expect_peek(Kind::LBrace) {
+            return None;
+        }
+
+        let alternative = self.parse_block_statement();
+
+        Some(Expression::If {
+            branches,
+            alternative: Some(Box::new(alternative)),
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_function_literal(&mut self) -> Option<Expression> {
+        let start = self.current_token;
+
+        if !self.expect_peek(Kind::LParen) {
+            return None;
+        }
+
+        let parameters = self.parse_function_parameters()?;
+
+        if !self.expect_peek(Kind::LBrace) {
+            return None;
+        }
+
+        self.scopes.push(HashMap::new());
+        for parameter in &parameters {
+            self.declare_pattern(&parameter.pattern, false);
+        }
+
+        // A function body starts outside of any loop, even when the
+        // function is defined inside one.
+        let loop_depth = std::mem::take(&mut self.loop_depth);
+        let body = self.parse_block_statement();
+        self.loop_depth = loop_depth;
+
+        self.scopes.pop();
+
+        Some(Expression::FunctionLiteral {
+            name: None,
+            parameters,
+            body,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_block_statement(&mut self) -> BlockStatement {
+        self.nested(|parser| Some(parser.parse_block_statement_body()))
+            .unwrap_or(BlockStatement {
+                statements: vec![],
+                span: self.current_token.span,
+            })
+    }
+
+    fn parse_block_statement_body(&mut self) -> BlockStatement {
+        let start = self.current_token;
+        let mut statements = vec![];
+        self.next_token();
+
+        let depth = self.braces;
+        while self.current_token.kind != Kind::RBrace && self.current_token.kind != Kind::Eof {
+            match self.parse_statement() {
+                Some(statement) => statements.push(statement),
+                // The statement failed on the brace closing this block.
+                None if self.current_token.kind == Kind::RBrace && self.braces == depth => break,
+                None => self.skip_statement(depth),
+            }
+            self.next_token();
+        }
+
+        BlockStatement {
+            statements,
+            span: self.span_from(start),
+        }
+    }
+
+    fn parse_function_parameters(&mut self) -> Option<Vec<Parameter>> {
+        let mut parameters: Vec<Parameter> = vec![];
+
+        if self.peek_token.kind == Kind::RParen {
+            self.next_token();
+            return Some(parameters);
+        }
+
+        loop {
+            if parameters.last().is_some_and(|parameter| parameter.rest) {
+                self.error(
+                    self.peek_token,
+                    "rest parameter must be the last parameter".to_string(),
+                );
+                return None;
+            }
+
+            let parameter = self.parse_function_parameter()?;
+
+            let follows_default = parameters.last().is_some_and(|p| p.default.is_some());
+            if follows_default && parameter.default.is_none() && !parameter.rest {
+                self.error(
+                    self.current_token,
+                    format!(
+                        "parameter {} needs a default value because it follows one that has one",
+                        parameter.pattern
+                    ),
+                );
+                return None;
+            }
+
+            parameters.push(parameter);
+
+            if self.peek_token.kind != Kind::Comma {
+                break;
+            }
+            self.next_token();
+        }
+
+        if !self.expect_peek(Kind::RParen) {
+            return None;
+        }
+
+        Some(parameters)
+    }
+
+    fn parse_function_parameter(&mut self) -> Option<Parameter> {
+        let rest = self.peek_token.kind == Kind::DotDotDot;
+        if rest {
+            self.next_token();
+        }
+
+        let pattern = if rest {
+            if !self.expect_peek(Kind::Ident) {
+                return None;
+            }
+            Pattern::Binding(self.current_identifier())
+        } else {
+            self.next_token();
+            self.parse_pattern()?
+        };
+
+        let mut default = None;
+        if self.peek_token.kind == Kind::Assign {
+            self.next_token();
+            if rest {
+                self.error(
+                    self.current_token,
+                    "rest parameter cannot have a default value".to_string(),
+                );
+                return None;
+            }
+
+            self.next_token();
+            default = Some(self.parse_expression(LOWEST)?);
+        }
+
+        Some(Parameter {
+            pattern,
+            default,
+            rest,
+        })
+    }
+
+    fn current_identifier(&self) -> Identifer {
+        Identifer {
+            value: self.current_token.literal.to_string(),
+            span: self.current_token.span,
+            position: self.current_token.position,
+        }
+    }
+
+    fn declare(&mut self, name: &Identifer, mutable: bool) {
+        let binding = Binding {
+            position: name.position,
+            mutable,
+        };
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.value.clone(), binding);
+        }
+    }
+
+    fn declare_pattern(&mut self, pattern: &Pattern, mutable: bool) {
+        for name in pattern.bindings() {
+            self.declare(name, mutable);
+        }
+    }
+
+    /// Names that are not found may still be declared by an earlier program
+    /// sharing the same evaluator, so those are left to the evaluator.
+    fn lookup(&self, name: &str) -> Option<&Binding> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    fn parse_call_expression(&mut self, function: Expression, start: Token) -> Option<Expression> {
+        Some(Expression::Call {
+            function: Box::new(function),
+            arguments: self.parse_list(Kind::RParen, Self::parse_call_argument)?,
+            position: start.position,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_call_argument(&mut self) -> Option<Expression> {
+        if self.current_token.kind == Kind::DotDotDot {
+            let start = self.current_token;
+            self.next_token();
+            let value = self.parse_expression(LOWEST)?;
+            return Some(Expression::Spread {
+                value: Box::new(value),
+                span: self.span_from(start),
+            });
+        }
+
+        self.parse_expression(LOWEST)
+    }
+
+    fn parse_index_expression(&mut self, left: Expression, start: Token) -> Option<Expression> {
+        self.next_token();
+        let index = self.parse_expression(LOWEST)?;
+
+        if !self.expect_peek(Kind::RBracket) {
+            return None;
+        }
+
+        Some(Expression::Index {
+            left: Box::new(left),
+            index: Box::new(index),
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_member_expression(&mut self, object: Expression, start: Token) -> Option<Expression> {
+        if !self.expect_peek(Kind::Ident) {
+            return None;
+        }
+
+        Some(Expression::Member {
+            object: Box::new(object),
+            property: self.current_token.literal.to_string(),
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_hash_literal(&mut self) -> Option<Expression> {
+        let start = self.current_token;
+        let mut pairs = vec![];
+
+        while self.peek_token.kind != Kind::RBrace {
+            self.next_token();
+            let key = self.parse_expression(LOWEST)?;
+
+            if !self.expect_peek(Kind::Colon) {
+                return None;
+            }
+
+            self.next_token();
+            let value = self.parse_expression(LOWEST)?;
+            pairs.push((key, value));
+
+            if self.peek_token.kind != Kind::RBrace && !self.expect_peek(Kind::Comma) {
+                return None;
+            }
+        }
+
+        self.next_token();
+
+        Some(Expression::HashLiteral {
+            pairs,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parses comma separated expressions up to and including `end`.
+    fn parse_expression_list(&mut self, end: Kind) -> Option<Vec<Expression>> {
+        self.parse_list(end, |parser| parser.parse_expression(LOWEST))
+    }
+
+    /// Parses comma separated items up to `end`, starting each item with
+    /// `parse_item` on its first token.
+    fn parse_list(
+        &mut self,
+        end: Kind,
+        parse_item: fn(&mut Self) -> Option<Expression>,
+    ) -> Option<Vec<Expression>> {
+        let mut list = vec![];
+
+        if self.peek_token.kind == end {
+            self.next_token();
+            return Some(list);
+        }
+
+        self.next_token();
+        list.push(parse_item(self)?);
+
+        while self.peek_token.kind == Kind::Comma {
+            self.next_token();
+            self.next_token();
+            list.push(parse_item(self)?);
+        }
+
+        if !self.expect_peek(end) {
+            return None;
+        }
+
+        Some(list)
+    }
+
+    fn peek_precedence(&self) -> i32 {
+        match self.peek_token.kind {
+            Kind::Assign
+            | Kind::PlusAssign
+            | Kind::MinusAssign
+            | Kind::AsterixAssign
+            | Kind::SlashAssign => ASSIGN,
+            Kind::Question => CONDITIONAL,
+            Kind::Or => OR,
+            Kind::And => AND,
+            Kind::Eq | Kind::Ne => EQUALITY,
+            Kind::Lt | Kind::Gt | Kind::LtEq | Kind::GtEq => LESS_GREATER,
+            Kind::DotDot | Kind::DotDotEq => RANGE,
             Kind::Plus | Kind::Minus => SUM,
-            Kind::Asterix | Kind::Slash => PRODUCT,
+            Kind::Asterix | Kind::Slash | Kind::Percent => PRODUCT,
             Kind::LParen => CALL,
+            Kind::LBracket | Kind::Dot => INDEX,
             _ => LOWEST,
         }
     }
 
     fn current_precedence(&self) -> i32 {
         match self.current_token.kind {
+            Kind::Assign
+            | Kind::PlusAssign
+            | Kind::MinusAssign
+            | Kind::AsterixAssign
+            | Kind::SlashAssign => ASSIGN,
+            Kind::Question => CONDITIONAL,
+            Kind::Or => OR,
+            Kind::And => AND,
             Kind::Eq | Kind::Ne => EQUALITY,
-            Kind::Lt | Kind::Gt => LESS_GREATER,
+            Kind::Lt | Kind::Gt | Kind::LtEq | Kind::GtEq => LESS_GREATER,
+            Kind::DotDot | Kind::DotDotEq => RANGE,
             Kind::Plus | Kind::Minus => SUM,
-            Kind::Asterix | Kind::Slash => PRODUCT,
+            Kind::Asterix | Kind::Slash | Kind::Percent => PRODUCT,
             Kind::LParen => CALL,
+            Kind::LBracket | Kind::Dot => INDEX,
             _ => LOWEST,
         }
     }
 
+    /// Runs `parse` one level deeper, failing once nesting passes
+    /// `MAX_NESTING`. The rest of the input is then skipped, as every level
+    /// it is nested in has failed too.
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Option<T>) -> Option<T> {
+        if self.nesting == MAX_NESTING {
+            let message = format!("nesting is more than {MAX_NESTING} levels deep");
+            self.error(self.current_token, message);
+            while self.current_token.kind != Kind::Eof {
+                self.next_token();
+            }
+            return None;
+        }
+
+        self.nesting += 1;
+        let result = stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT, || parse(self));
+        self.nesting -= 1;
+        result
+    }
+
+    fn error(&mut self, token: Token, message: String) {
+        self.errors.push(format!("{}: {message}", token.position));
+    }
+
+    fn warning(&mut self, token: Token, message: String) {
+        self.warnings.push(format!("{}: {message}", token.position));
+    }
+
+    fn illegal_token_error(&mut self, token: Token, reason: Reason) {
+        self.error(token, reason.to_string());
+    }
+
     fn peek_error(&mut self, token_kind: Kind) {
-        self.errors.push(format!(
-            "expected next token to be {token_kind:?}, got {:?} instead",
-            self.peek_token.kind
-        ));
+        match self.peek_token.kind {
+            Kind::Illegal(reason) => self.illegal_token_error(self.peek_token, reason),
+            kind => self.error(
+                self.peek_token,
+                format!("expected next token to be {token_kind:?}, got {kind:?} instead"),
+            ),
+        }
     }
 
     fn expect_peek(&mut self, expected_token: Kind) -> bool {
@@ -363,18 +1367,56 @@ impl Parser {
 #[cfg(test)]
 mod tests {
     use crate::lexer::Lexer;
-    use crate::parser::ast::{Expression, Node, Statement};
+    use crate::parser::ast::{Expression, Node, Pattern, Statement};
+    use crate::token::Position;
 
     use super::Parser;
 
     fn check_parser_errors(parser: &Parser) {
-        if parser.errors.len() != 0 {
+        if !parser.errors.is_empty() {
             println!("Got {} errors.", parser.errors.len());
             for error in &parser.errors {
                 println!("ERROR: {error}");
             }
 
-            assert!(false);
+            panic!("parser had errors");
+        }
+    }
+
+    #[test]
+    fn mutable_bindings() {
+        // Arrange
+        let tests = vec![
+            ("let mut x = 5;", "let mut x = 5;"),
+            ("let mut x = 5; x += 1", "let mut x = 5;(x += 1)"),
+            (
+                "let x = 1; let mut x = x; x = 2",
+                "let x = 1;let mut x = x;(x = 2)",
+            ),
+            (
+                "let mut xs = [1]; xs[0] = 2",
+                "let mut xs = [1];((xs[0]) = 2)",
+            ),
+            (
+                "let mut n = 0; let f = fn() { n += 1 };",
+                "let mut n = 0;let f = fn() { (n += 1) };",
+            ),
+            ("y = 1", "(y = 1)"),
+            (
+                "let mut x = 10; for (x in [1, 2]) {} x = 5",
+                "let mut x = 10;for (x in [1, 2]) {  }(x = 5)",
+            ),
+        ];
+
+        // Act
+        for (input, expected) in tests {
+            let lexer = Lexer::new(input);
+            let mut parser = Parser::new(lexer);
+            let program = parser.parse_program();
+
+            // Assert
+            check_parser_errors(&parser);
+            assert_eq!(program.to_string(), expected);
         }
     }
 
@@ -384,7 +1426,7 @@ mod tests {
         let input = "let x = 5;".to_string();
 
         // Act
-        let lexer = Lexer::new(input);
+        let lexer = Lexer::new(&input);
         let mut parser = Parser::new(lexer);
         let program = parser.parse_program();
 
@@ -392,10 +1434,10 @@ mod tests {
         check_parser_errors(&parser);
         assert_eq!(program.statements.len(), 1);
         match &program.statements[0] {
-            Statement::Let { name, value } => {
-                assert_eq!(name.token_literal(), "x".to_string());
+            Statement::Let { pattern, value, .. } => {
+                assert_eq!(pattern.token_literal(), "x".to_string());
                 match value {
-                    Expression::IntegerLiteral(value) => {
+                    Expression::IntegerLiteral { value, .. } => {
                         assert_eq!(*value, 5);
                     }
                     e => panic!("{e} is not an integer"),
@@ -411,7 +1453,7 @@ mod tests {
         let input = "return 5;".to_string();
 
         // Act
-        let lexer = Lexer::new(input);
+        let lexer = Lexer::new(&input);
         let mut parser = Parser::new(lexer);
         let program = parser.parse_program();
 
@@ -419,9 +1461,9 @@ mod tests {
         check_parser_errors(&parser);
         assert_eq!(program.statements.len(), 1);
         match &program.statements[0] {
-            Statement::Return(value) => {
+            Statement::Return { value, .. } => {
                 match value {
-                    Expression::IntegerLiteral(value) => {
+                    Expression::IntegerLiteral { value, .. } => {
                         assert_eq!(*value, 5);
                     }
                     e => panic!("{e} is not an integer"),
@@ -437,7 +1479,7 @@ mod tests {
         let input = "foobar;".to_string();
 
         // Act
-        let lexer = Lexer::new(input);
+        let lexer = Lexer::new(&input);
         let mut parser = Parser::new(lexer);
         let program = parser.parse_program();
 
@@ -446,14 +1488,14 @@ mod tests {
         assert_eq!(program.statements.len(), 1);
         let expression_statement = match &program.statements[0] {
             Statement::Expression {
-                token: _,
+                span: _,
                 expression,
             } => expression,
             s => panic!("{s} is not an expression statement"),
         };
 
         match expression_statement {
-            Expression::Identifier(value) => {
+            Expression::Identifier { name: value, .. } => {
                 assert_eq!(expression_statement.token_literal(), "foobar");
                 assert_eq!(value, "foobar");
             }
@@ -467,7 +1509,7 @@ mod tests {
         let input = "5;".to_string();
 
         // Act
-        let lexer = Lexer::new(input);
+        let lexer = Lexer::new(&input);
         let mut parser = Parser::new(lexer);
         let program = parser.parse_program();
 
@@ -476,14 +1518,14 @@ mod tests {
         assert_eq!(program.statements.len(), 1);
         let expression_statement = match &program.statements[0] {
             Statement::Expression {
-                token: _,
+                span: _,
                 expression,
             } => expression,
             s => panic!("{s} is not an expression statement"),
         };
 
         match expression_statement {
-            Expression::IntegerLiteral(value) => {
+            Expression::IntegerLiteral { value, .. } => {
                 assert_eq!(expression_statement.token_literal(), "5");
                 assert_eq!(*value, 5);
             }
@@ -492,31 +1534,108 @@ mod tests {
     }
 
     #[test]
-    fn boolean_literal_expression() {
+    fn minimum_integer_literal() {
         // Arrange
-        let input = "true;".to_string();
-
-        // Act
-        let lexer = Lexer::new(input);
-        let mut parser = Parser::new(lexer);
-        let program = parser.parse_program();
+        let tests = vec![
+            ("-9223372036854775808", "-9223372036854775808"),
+            ("-0x8000_0000_0000_0000 + 1", "(-9223372036854775808 + 1)"),
+            (
+                "let -9223372036854775808 = x;",
+                "let -9223372036854775808 = x;",
+            ),
+        ];
 
-        // Assert
-        check_parser_errors(&parser);
-        assert_eq!(program.statements.len(), 1);
-        let expression_statement = match &program.statements[0] {
-            Statement::Expression {
-                token: _,
-                expression,
-            } => expression,
-            s => panic!("{s} is not an expression statement"),
-        };
+        for (input, expected) in tests {
+            // Act
+            let lexer = Lexer::new(input);
+            let mut parser = Parser::new(lexer);
+            let program = parser.parse_program();
 
-        match expression_statement {
-            Expression::BooleanLiteral(value) => {
-                assert_eq!(expression_statement.token_literal(), "true");
-                assert_eq!(*value, true);
-            }
+            // Assert
+            check_parser_errors(&parser);
+            match &program.statements[0] {
+                Statement::Expression {
+                    expression: Expression::IntegerLiteral { value, .. },
+                    ..
+                }
+                | Statement::Let {
+                    pattern: Pattern::Literal(Expression::IntegerLiteral { value, .. }),
+                    ..
+                } => assert_eq!(*value, i64::MIN, "{input}"),
+                Statement::Expression {
+                    expression: Expression::Infix { left, .. },
+                    ..
+                } => assert!(
+                    matches!(
+                        **left,
+                        Expression::IntegerLiteral {
+                            value: i64::MIN,
+                            ..
+                        }
+                    ),
+                    "{input}"
+                ),
+                s => panic!("{s} does not start with an integer literal"),
+            }
+            assert_eq!(program.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn float_literal_expression() {
+        // Arrange
+        let tests = vec![
+            ("2.75;", 2.75, "2.75"),
+            ("1e-9;", 1e-9, "1e-9"),
+            ("2.0", 2.0, "2.0"),
+        ];
+
+        for (input, expected, display) in tests {
+            // Act
+            let lexer = Lexer::new(input);
+            let mut parser = Parser::new(lexer);
+            let program = parser.parse_program();
+
+            // Assert
+            check_parser_errors(&parser);
+            assert_eq!(program.statements.len(), 1);
+            match &program.statements[0] {
+                Statement::Expression {
+                    span: _,
+                    expression: Expression::FloatLiteral { value, .. },
+                } => assert_eq!(*value, expected),
+                s => panic!("{s} is not a float literal"),
+            };
+            assert_eq!(program.to_string(), display);
+        }
+    }
+
+    #[test]
+    fn boolean_literal_expression() {
+        // Arrange
+        let input = "true;".to_string();
+
+        // Act
+        let lexer = Lexer::new(&input);
+        let mut parser = Parser::new(lexer);
+        let program = parser.parse_program();
+
+        // Assert
+        check_parser_errors(&parser);
+        assert_eq!(program.statements.len(), 1);
+        let expression_statement = match &program.statements[0] {
+            Statement::Expression {
+                span: _,
+                expression,
+            } => expression,
+            s => panic!("{s} is not an expression statement"),
+        };
+
+        match expression_statement {
+            Expression::BooleanLiteral { value, .. } => {
+                assert_eq!(expression_statement.token_literal(), "true");
+                assert!(*value);
+            }
             e => panic!("{e} is not an identifier"),
         };
     }
@@ -528,7 +1647,7 @@ mod tests {
 
         // Act
         for (input, op, integer_value) in prefix_tests {
-            let lexer = Lexer::new(input.to_string());
+            let lexer = Lexer::new(input);
             let mut parser = Parser::new(lexer);
             let program = parser.parse_program();
 
@@ -537,17 +1656,21 @@ mod tests {
             assert_eq!(program.statements.len(), 1);
             let expression_statement = match &program.statements[0] {
                 Statement::Expression {
-                    token: _,
+                    span: _,
                     expression,
                 } => expression,
                 s => panic!("{s} is not an expression statement"),
             };
 
             match expression_statement {
-                Expression::Prefix { operator, right } => {
+                Expression::Prefix {
+                    operator, right, ..
+                } => {
                     assert_eq!(operator, op);
                     match &**right {
-                        Expression::IntegerLiteral(i) => assert_eq!(*i, integer_value),
+                        Expression::IntegerLiteral { value: i, .. } => {
+                            assert_eq!(*i, integer_value)
+                        }
                         e => panic!("{e} is not an integer literal"),
                     }
                 }
@@ -568,11 +1691,14 @@ mod tests {
             ("5 < 5;", 5, "<", 5),
             ("5 == 5;", 5, "==", 5),
             ("5 != 5;", 5, "!=", 5),
+            ("5 % 5;", 5, "%", 5),
+            ("5 >= 5;", 5, ">=", 5),
+            ("5 <= 5;", 5, "<=", 5),
         ];
 
         // Act
         for (input, left_value, op, right_value) in infix_tests {
-            let lexer = Lexer::new(input.to_string());
+            let lexer = Lexer::new(input);
             let mut parser = Parser::new(lexer);
             let program = parser.parse_program();
 
@@ -581,7 +1707,7 @@ mod tests {
             assert_eq!(program.statements.len(), 1);
             let expression_statement = match &program.statements[0] {
                 Statement::Expression {
-                    token: _,
+                    span: _,
                     expression,
                 } => expression,
                 s => panic!("{s} is not an expression statement"),
@@ -592,14 +1718,15 @@ mod tests {
                     left,
                     operator,
                     right,
+                    ..
                 } => {
                     match &**right {
-                        Expression::IntegerLiteral(i) => assert_eq!(*i, right_value),
+                        Expression::IntegerLiteral { value: i, .. } => assert_eq!(*i, right_value),
                         e => panic!("{e} is not an integer literal"),
                     }
                     assert_eq!(operator, op);
                     match &**left {
-                        Expression::IntegerLiteral(i) => assert_eq!(*i, left_value),
+                        Expression::IntegerLiteral { value: i, .. } => assert_eq!(*i, left_value),
                         e => panic!("{e} is not an integer literal"),
                     }
                 }
@@ -615,11 +1742,13 @@ mod tests {
             ("true == true;", true, "==", true),
             ("true != false;", true, "!=", false),
             ("false == false;", false, "==", false),
+            ("true && false;", true, "&&", false),
+            ("false || true;", false, "||", true),
         ];
 
         // Act
         for (input, left_value, op, right_value) in infix_tests {
-            let lexer = Lexer::new(input.to_string());
+            let lexer = Lexer::new(input);
             let mut parser = Parser::new(lexer);
             let program = parser.parse_program();
 
@@ -628,7 +1757,7 @@ mod tests {
             assert_eq!(program.statements.len(), 1);
             let expression_statement = match &program.statements[0] {
                 Statement::Expression {
-                    token: _,
+                    span: _,
                     expression,
                 } => expression,
                 s => panic!("{s} is not an expression statement"),
@@ -639,14 +1768,15 @@ mod tests {
                     left,
                     operator,
                     right,
+                    ..
                 } => {
                     match &**right {
-                        Expression::BooleanLiteral(i) => assert_eq!(*i, right_value),
+                        Expression::BooleanLiteral { value: i, .. } => assert_eq!(*i, right_value),
                         e => panic!("{e} is not a boolean literal"),
                     }
                     assert_eq!(operator, op);
                     match &**left {
-                        Expression::BooleanLiteral(i) => assert_eq!(*i, left_value),
+                        Expression::BooleanLiteral { value: i, .. } => assert_eq!(*i, left_value),
                         e => panic!("{e} is not a boolean literal"),
                     }
                 }
@@ -667,7 +1797,7 @@ mod tests {
             ("a * b / c", "((a * b) / c)"),
             ("a + b / c", "(a + (b / c))"),
             ("a + b * c + d / e - f", "(((a + (b * c)) + (d / e)) - f)"),
-            ("3 + 4; -5 * 5", "(3 + 4)((-5) * 5)"),
+            ("3 + 4; -5 * 5", "(3 + 4); ((-5) * 5)"),
             ("5 > 4 == 3 < 4", "((5 > 4) == (3 < 4))"),
             ("5 < 4 != 3 > 4", "((5 < 4) != (3 > 4))"),
             (
@@ -688,11 +1818,22 @@ mod tests {
                 "add(a + b + c * d / f + g)",
                 "add((((a + b) + ((c * d) / f)) + g))",
             ),
+            (
+                "a * [1, 2, 3, 4][b * c] * d",
+                "((a * ([1, 2, 3, 4][(b * c)])) * d)",
+            ),
+            (
+                "add(a * b[2], b[1], 2 * [1, 2][1])",
+                "add((a * (b[2])), (b[1]), (2 * ([1, 2][1])))",
+            ),
+            ("0..n + 1", "(0..(n + 1))"),
+            ("a..=b == c", "((a..=b) == c)"),
+            ("xs[0..2]", "(xs[(0..2)])"),
         ];
 
         // Act
         for (input, expected) in infix_tests {
-            let lexer = Lexer::new(input.to_string());
+            let lexer = Lexer::new(input);
             let mut parser = Parser::new(lexer);
             let program = parser.parse_program();
 
@@ -712,11 +1853,17 @@ mod tests {
             ("false", "false"),
             ("3 > 5 == false", "((3 > 5) == false)"),
             ("3 < 5 == true", "((3 < 5) == true)"),
+            ("a || b && c", "(a || (b && c))"),
+            ("a && b || c", "((a && b) || c)"),
+            ("a && b == c", "(a && (b == c))"),
+            ("a <= b == c >= d", "((a <= b) == (c >= d))"),
+            ("!a || b", "((!a) || b)"),
+            ("a % b * c + d", "(((a % b) * c) + d)"),
         ];
 
         // Act
         for (input, expected) in tests {
-            let lexer = Lexer::new(input.to_string());
+            let lexer = Lexer::new(input);
             let mut parser = Parser::new(lexer);
             let program = parser.parse_program();
 
@@ -728,13 +1875,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn assignment_expressions() {
+        // Arrange
+        let tests = vec![
+            ("x = 5", "(x = 5)"),
+            ("x = y = 5", "(x = (y = 5))"),
+            ("x += 1 + 2", "(x += (1 + 2))"),
+            ("x -= y *= 2", "(x -= (y *= 2))"),
+            ("x /= 2", "(x /= 2)"),
+            ("x = a || b == c", "(x = (a || (b == c)))"),
+            ("xs[i + 1] = 5", "((xs[(i + 1)]) = 5)"),
+            ("h[\"k\"][0] *= 3", "(((h[\"k\"])[0]) *= 3)"),
+        ];
+
+        // Act
+        for (input, expected) in tests {
+            let lexer = Lexer::new(input);
+            let mut parser = Parser::new(lexer);
+            let program = parser.parse_program();
+
+            // Assert
+            check_parser_errors(&parser);
+            assert_eq!(program.to_string(), expected);
+        }
+    }
+
     #[test]
     fn if_expression() {
         // Arrange
         let input = "if (x < y) { x };".to_string();
 
         // Act
-        let lexer = Lexer::new(input);
+        let lexer = Lexer::new(&input);
         let mut parser = Parser::new(lexer);
         let program = parser.parse_program();
 
@@ -743,7 +1916,7 @@ mod tests {
         assert_eq!(program.statements.len(), 1);
         let expression_statement = match &program.statements[0] {
             Statement::Expression {
-                token: _,
+                span: _,
                 expression,
             } => expression,
             s => panic!("{s} is not an expression statement"),
@@ -751,10 +1924,12 @@ mod tests {
 
         match expression_statement {
             Expression::If {
-                condition,
-                consequence,
+                branches,
                 alternative,
+                ..
             } => {
+                assert_eq!(branches.len(), 1);
+                let (condition, consequence) = &branches[0];
                 assert_eq!(condition.to_string(), "(x < y)");
                 assert_eq!(consequence.to_string(), "x");
                 assert_eq!(*alternative, None);
@@ -769,7 +1944,7 @@ mod tests {
         let input = "if (x < y) { x } else { y };".to_string();
 
         // Act
-        let lexer = Lexer::new(input);
+        let lexer = Lexer::new(&input);
         let mut parser = Parser::new(lexer);
         let program = parser.parse_program();
 
@@ -778,7 +1953,7 @@ mod tests {
         assert_eq!(program.statements.len(), 1);
         let expression_statement = match &program.statements[0] {
             Statement::Expression {
-                token: _,
+                span: _,
                 expression,
             } => expression,
             s => panic!("{s} is not an expression statement"),
@@ -786,10 +1961,12 @@ mod tests {
 
         match expression_statement {
             Expression::If {
-                condition,
-                consequence,
+                branches,
                 alternative,
+                ..
             } => {
+                assert_eq!(branches.len(), 1);
+                let (condition, consequence) = &branches[0];
                 assert_eq!(condition.to_string(), "(x < y)");
                 assert_eq!(consequence.to_string(), "x");
                 assert_eq!(alternative.as_ref().unwrap().to_string(), "y");
@@ -798,13 +1975,203 @@ mod tests {
         };
     }
 
+    #[test]
+    fn else_if_chain() {
+        // Arrange
+        let input = "if (a) { 1 } else if (b) { 2 } else if (c) { 3 } else { 4 }";
+
+        // Act
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let program = parser.parse_program();
+
+        // Assert
+        check_parser_errors(&parser);
+        assert_eq!(program.statements.len(), 1);
+        match &program.statements[0] {
+            Statement::Expression {
+                expression:
+                    Expression::If {
+                        branches,
+                        alternative,
+                        ..
+                    },
+                ..
+            } => {
+                let conditions = branches
+                    .iter()
+                    .map(|(condition, _)| condition.to_string())
+                    .collect::<Vec<_>>();
+                assert_eq!(conditions, vec!["a", "b", "c"]);
+                assert_eq!(alternative.as_ref().unwrap().to_string(), "4");
+            }
+            s => panic!("{s} is not an if expression"),
+        };
+    }
+
+    #[test]
+    fn conditional_expressions() {
+        // Arrange
+        let tests = vec![
+            ("a ? b : c", "(a ? b : c)"),
+            ("a ? b : c ? d : e", "(a ? b : (c ? d : e))"),
+            ("a ? b ? c : d : e", "(a ? (b ? c : d) : e)"),
+            ("a || b ? c + 1 : d * 2", "((a || b) ? (c + 1) : (d * 2))"),
+            ("x = a ? b : c", "(x = (a ? b : c))"),
+            ("f(a ? b : c, d)", "f((a ? b : c), d)"),
+            ("{a ? b : c: d}", "{(a ? b : c): d}"),
+        ];
+
+        for (input, expected) in tests {
+            // Act
+            let lexer = Lexer::new(input);
+            let mut parser = Parser::new(lexer);
+            let program = parser.parse_program();
+
+            // Assert
+            check_parser_errors(&parser);
+            assert_eq!(program.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn display_round_trips() {
+        // Arrange
+        let tests = vec![
+            "if (a) { 1 } else if (b) { 2 } else { 3 }",
+            "if (x < y) { let z = x; z } else if (x == y) { 0 }",
+            "if (a) { if (b) { 1 } else { 2 } } else { c ? d : e }",
+            "a ? b : c ? d : e",
+            "let f = fn(n) { n < 2 ? n : f(n - 1) + f(n - 2) }; f(10)",
+            "a; b; if (c) { d; e }",
+            "import \"lib/utils.gb\" as utils; export let x = utils.double(2);",
+            "export fn f(a) { a.b.c[0] }",
+            "try { f() } catch (e) { e.message }",
+            "try { throw {\"message\": \"no\"}; } finally { cleanup() }",
+            "let x = try { 1 } catch (e) { 2 } finally { 3 }; x",
+        ];
+
+        for input in tests {
+            // Act
+            let first = Parser::new(Lexer::new(input)).parse_program().to_string();
+            let mut parser = Parser::new(Lexer::new(&first));
+            let second = parser.parse_program().to_string();
+
+            // Assert
+            check_parser_errors(&parser);
+            assert_eq!(second, first, "{input}");
+        }
+    }
+
+    #[test]
+    fn match_expressions() {
+        // Arrange
+        let tests = vec![
+            (
+                "match (x) { 0 => \"zero\", -1.5 => 1, _ => x }",
+                "match (x) { 0 => { \"zero\" }, -1.5 => { 1 }, _ => { x } }",
+            ),
+            (
+                "match (x) { n if n > 0 => { let y = n; y }, n => 0 }",
+                "match (x) { n if (n > 0) => { let y = n;y }, n => { 0 } }",
+            ),
+            (
+                "match (xs) { [] => 0, [x, [y], ...rest] => x, _ => 1, }",
+                "match (xs) { [] => { 0 }, [x, [y], ...rest] => { x }, _ => { 1 } }",
+            ),
+            (
+                "match (h) { {\"k\": v, 1: true} => v, {} => 0, _ => 1 }",
+                "match (h) { {\"k\": v, 1: true} => { v }, {} => { 0 }, _ => { 1 } }",
+            ),
+        ];
+
+        for (input, expected) in tests {
+            // Act
+            let lexer = Lexer::new(input);
+            let mut parser = Parser::new(lexer);
+            let program = parser.parse_program();
+
+            // Assert
+            check_parser_errors(&parser);
+            assert!(parser.warnings.is_empty(), "{:?}", parser.warnings);
+            assert_eq!(program.to_string(), expected);
+
+            let mut parser = Parser::new(Lexer::new(expected));
+            assert_eq!(parser.parse_program().to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn destructuring() {
+        // Arrange
+        let tests = vec![
+            ("let [a, b, ...rest] = xs;", "let [a, b, ...rest] = xs;"),
+            (
+                "let {name, age} = person;",
+                "let {\"name\": name, \"age\": age} = person;",
+            ),
+            (
+                "let mut {\"k\": [v, _]} = h;",
+                "let mut {\"k\": [v, _]} = h;",
+            ),
+            (
+                "fn([a, b], {name} = {}) {}",
+                "fn([a, b], {\"name\": name} = {}) {  }",
+            ),
+            ("fn f(x, [y], ...rest) {}", "fn f(x, [y], ...rest) {  }"),
+        ];
+
+        for (input, expected) in tests {
+            // Act
+            let lexer = Lexer::new(input);
+            let mut parser = Parser::new(lexer);
+            let program = parser.parse_program();
+
+            // Assert
+            check_parser_errors(&parser);
+            assert_eq!(program.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn non_exhaustive_match_warning() {
+        // Arrange
+        let tests = vec![
+            (
+                "match (x) { 0 => 1 }",
+                vec!["1:1: non-exhaustive match: add a `_` arm to handle any other value"],
+            ),
+            (
+                "let y =\n  match (x) { n if n > 0 => 1 }",
+                vec!["2:3: non-exhaustive match: add a `_` arm to handle any other value"],
+            ),
+            (
+                "match (x) { [a] => a, {} => 0 }",
+                vec!["1:1: non-exhaustive match: add a `_` arm to handle any other value"],
+            ),
+            ("match (x) { 0 => 1, _ => 2 }", vec![]),
+            ("match (x) { 0 => 1, other => 2 }", vec![]),
+        ];
+
+        for (input, expected) in tests {
+            // Act
+            let lexer = Lexer::new(input);
+            let mut parser = Parser::new(lexer);
+            parser.parse_program();
+
+            // Assert
+            check_parser_errors(&parser);
+            assert_eq!(parser.warnings, expected, "{input}");
+        }
+    }
+
     #[test]
     fn function_literal_expression() {
         // Arrange
         let input = "fn(x, y) { x + y; }".to_string();
 
         // Act
-        let lexer = Lexer::new(input);
+        let lexer = Lexer::new(&input);
         let mut parser = Parser::new(lexer);
         let program = parser.parse_program();
 
@@ -813,20 +2180,26 @@ mod tests {
         assert_eq!(program.statements.len(), 1);
         let expression_statement = match &program.statements[0] {
             Statement::Expression {
-                token: _,
+                span: _,
                 expression,
             } => expression,
             s => panic!("{s} is not an expression statement"),
         };
 
         match expression_statement {
-            Expression::FunctionLiteral { parameters, body } => {
+            Expression::FunctionLiteral {
+                name,
+                parameters,
+                body,
+                ..
+            } => {
+                assert_eq!(*name, None);
                 assert_eq!(parameters[0].token_literal(), "x");
                 assert_eq!(parameters[1].token_literal(), "y");
                 assert_eq!(body.statements.len(), 1);
                 match &body.statements[0] {
                     Statement::Expression {
-                        token: _,
+                        span: _,
                         expression,
                     } => {
                         assert_eq!(expression.to_string(), "(x + y)")
@@ -838,6 +2211,60 @@ mod tests {
         };
     }
 
+    #[test]
+    fn function_declarations() {
+        // Arrange
+        let tests = vec![
+            ("fn add(x, y) { x + y }", "fn add(x, y) { (x + y) }"),
+            ("fn nothing() {}", "fn nothing() {  }"),
+            (
+                "fn fib(n) { if (n < 2) { n } else { fib(n - 1) + fib(n - 2) } }",
+                "fn fib(n) { if ((n < 2)) { n } else { (fib((n - 1)) + fib((n - 2))) } }",
+            ),
+            ("fn(x) { x }(1)", "fn(x) { x }(1)"),
+        ];
+
+        for (input, expected) in tests {
+            // Act
+            let lexer = Lexer::new(input);
+            let mut parser = Parser::new(lexer);
+            let program = parser.parse_program();
+
+            // Assert
+            check_parser_errors(&parser);
+            assert_eq!(program.statements.len(), 1);
+            assert_eq!(program.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn let_records_function_names() {
+        // Arrange
+        let tests = vec![
+            ("let add = fn(x, y) { x + y };", Some("add")),
+            ("let mut f = fn() {};", Some("f")),
+            ("let x = 5;", None),
+        ];
+
+        for (input, expected) in tests {
+            // Act
+            let lexer = Lexer::new(input);
+            let mut parser = Parser::new(lexer);
+            let program = parser.parse_program();
+
+            // Assert
+            check_parser_errors(&parser);
+            let name = match &program.statements[0] {
+                Statement::Let {
+                    value: Expression::FunctionLiteral { name, .. },
+                    ..
+                } => name.as_deref(),
+                _ => None,
+            };
+            assert_eq!(name, expected, "{input}");
+        }
+    }
+
     #[test]
     fn function_parameters() {
         // Arrange
@@ -849,7 +2276,7 @@ mod tests {
 
         for (input, expected) in tests {
             // Act
-            let lexer = Lexer::new(input);
+            let lexer = Lexer::new(&input);
             let mut parser = Parser::new(lexer);
             let program = parser.parse_program();
 
@@ -858,17 +2285,14 @@ mod tests {
             assert_eq!(program.statements.len(), 1);
             let expression_statement = match &program.statements[0] {
                 Statement::Expression {
-                    token: _,
+                    span: _,
                     expression,
                 } => expression,
                 s => panic!("{s} is not an expression statement"),
             };
 
             match expression_statement {
-                Expression::FunctionLiteral {
-                    parameters,
-                    body: _,
-                } => {
+                Expression::FunctionLiteral { parameters, .. } => {
                     assert_eq!(parameters.len(), expected.len());
                     for (i, parameter) in parameters.iter().enumerate() {
                         assert_eq!(parameter.token_literal(), expected[i]);
@@ -879,13 +2303,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn default_and_rest_parameters() {
+        // Arrange
+        let tests = vec![
+            ("fn(x, y = 10) { x }", "fn(x, y = 10) { x }"),
+            ("fn(x = 1 + 2, y = x) {}", "fn(x = (1 + 2), y = x) {  }"),
+            ("fn(first, ...rest) { rest }", "fn(first, ...rest) { rest }"),
+            ("fn(x = 1, ...rest) {}", "fn(x = 1, ...rest) {  }"),
+            ("fn f(...all) {}", "fn f(...all) {  }"),
+            ("f(...xs)", "f(...xs)"),
+            ("f(1, ...[2, 3], ...g(4))", "f(1, ...[2, 3], ...g(4))"),
+        ];
+
+        for (input, expected) in tests {
+            // Act
+            let lexer = Lexer::new(input);
+            let mut parser = Parser::new(lexer);
+            let program = parser.parse_program();
+
+            // Assert
+            check_parser_errors(&parser);
+            assert_eq!(program.to_string(), expected);
+        }
+    }
+
     #[test]
     fn call_expression() {
         // Arrange
         let input = "add(1, 2 * 3, 4 + 5);".to_string();
 
         // Act
-        let lexer = Lexer::new(input);
+        let lexer = Lexer::new(&input);
         let mut parser = Parser::new(lexer);
         let program = parser.parse_program();
 
@@ -894,7 +2343,7 @@ mod tests {
         assert_eq!(program.statements.len(), 1);
         let expression_statement = match &program.statements[0] {
             Statement::Expression {
-                token: _,
+                span: _,
                 expression,
             } => expression,
             s => panic!("{s} is not an expression statement"),
@@ -904,9 +2353,12 @@ mod tests {
             Expression::Call {
                 function,
                 arguments,
+                position,
+                ..
             } => {
+                assert_eq!(*position, Position::new(1, 1));
                 match &**function {
-                    Expression::Identifier(value) => {
+                    Expression::Identifier { name: value, .. } => {
                         assert_eq!(*value, "add".to_string());
                     }
                     e => panic!("{e} is not an identifier"),
@@ -919,4 +2371,534 @@ mod tests {
             e => panic!("{e} is not a function call"),
         };
     }
+
+    #[test]
+    fn string_literal_expression() {
+        // Arrange
+        let input = r#""hello \"world\"";"#;
+
+        // Act
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let program = parser.parse_program();
+
+        // Assert
+        check_parser_errors(&parser);
+        assert_eq!(program.statements.len(), 1);
+        match &program.statements[0] {
+            Statement::Expression {
+                span: _,
+                expression: Expression::StringLiteral { value, .. },
+            } => assert_eq!(value, "hello \"world\""),
+            s => panic!("{s} is not a string literal"),
+        };
+        assert_eq!(program.to_string(), input.trim_end_matches(';'));
+    }
+
+    /// Parses each input and asserts it fails with exactly the one error.
+    fn assert_parse_error(tests: Vec<(&str, &str)>) {
+        for (input, expected) in tests {
+            let lexer = Lexer::new(input);
+            let mut parser = Parser::new(lexer);
+            parser.parse_program();
+
+            assert_eq!(parser.errors, vec![expected], "{input}");
+        }
+    }
+
+    #[test]
+    fn illegal_token_diagnostics() {
+        // Arrange
+        let tests = vec![
+            ("let x = 5 @ 3;", "1:11: unexpected character '@'"),
+            ("let x = @;", "1:9: unexpected character '@'"),
+            ("let @ = 5;", "1:5: unexpected character '@'"),
+            ("let x = 0x;", "1:9: number literal is missing digits"),
+            ("let s =\n  \"abc", "2:3: unterminated string literal"),
+            (r#"let s = "\q";"#, "1:9: invalid escape sequence '\\q'"),
+            ("99999999999999999999", "1:1: integer literal is too large"),
+            (
+                "1 + 0xffff_ffff_ffff_ffff",
+                "1:5: integer literal is too large",
+            ),
+            ("let n = 0b12;", "1:9: invalid digit '2' in number literal"),
+            ("-9223372036854775809", "1:2: integer literal is too large"),
+            ("- 9223372036854775808", "1:3: integer literal is too large"),
+            (
+                "-9223372036854775808[0]",
+                "1:2: integer literal is too large",
+            ),
+        ];
+
+        // Act + Assert
+        assert_parse_error(tests);
+    }
+
+    #[test]
+    fn incomplete_expression_is_error() {
+        // Arrange
+        let tests = vec![
+            (
+                "puts(1 +);",
+                "1:9: can't parse expression that starts with RParen",
+            ),
+            (
+                "let x = ;",
+                "1:9: can't parse expression that starts with SemiColon",
+            ),
+        ];
+
+        // Act + Assert
+        assert_parse_error(tests);
+    }
+
+    #[test]
+    fn invalid_assignment_target_is_error() {
+        // Arrange
+        let tests = vec![
+            ("5 = 6", "1:1: invalid assignment target: 5"),
+            ("a + b = 6", "1:1: invalid assignment target: (a + b)"),
+            ("f() += 1", "1:1: invalid assignment target: f()"),
+            ("x = f(a) = 1", "1:5: invalid assignment target: f(a)"),
+            ("m.x = 1", "1:1: invalid assignment target: (m.x)"),
+        ];
+
+        // Act + Assert
+        assert_parse_error(tests);
+    }
+
+    #[test]
+    fn assign_to_immutable_binding_is_error() {
+        // Arrange
+        let tests = vec![
+            (
+                "let x = 1;\nx += 1;",
+                "2:1: cannot assign to immutable binding x (declared at 1:5, use `let mut x`)",
+            ),
+            (
+                "let xs = [1];\nlet f = fn() { xs[0] = 2 };",
+                "2:16: cannot assign to immutable binding xs (declared at 1:5, use `let mut xs`)",
+            ),
+            (
+                "let mut n = 1; let f = fn(n) { n = 2 };",
+                "1:32: cannot assign to immutable binding n (declared at 1:27, use `let mut n`)",
+            ),
+            (
+                "fn f() {}\nf = 1;",
+                "2:1: cannot assign to immutable binding f (declared at 1:4, use `let mut f`)",
+            ),
+            (
+                "let [a, b] = xs; a = 1;",
+                "1:18: cannot assign to immutable binding a (declared at 1:6, use `let mut a`)",
+            ),
+            (
+                "fn f({name}) { name = 1 }",
+                "1:16: cannot assign to immutable binding name (declared at 1:7, use `let mut name`)",
+            ),
+            (
+                "let mut n = 0; match (1) { n => { n = 2 } }",
+                "1:35: cannot assign to immutable binding n (declared at 1:28, use `let mut n`)",
+            ),
+            (
+                "for (i in 0..3) { i = 5; }",
+                "1:19: cannot assign to immutable binding i (declared at 1:6, use `let mut i`)",
+            ),
+            (
+                "import \"u.gb\" as u;\nu = 1;",
+                "2:1: cannot assign to immutable binding u (declared at 1:18, use `let mut u`)",
+            ),
+            (
+                "try { 1 } catch (e) { e = 2 }",
+                "1:23: cannot assign to immutable binding e (declared at 1:18, use `let mut e`)",
+            ),
+        ];
+
+        // Act + Assert
+        assert_parse_error(tests);
+    }
+
+    #[test]
+    fn invalid_parameters_are_errors() {
+        // Arrange
+        let tests = vec![
+            (
+                "fn 5() {}",
+                "1:4: expected next token to be LParen, got Int instead",
+            ),
+            (
+                "fn(x = 1, y) {}",
+                "1:11: parameter y needs a default value because it follows one that has one",
+            ),
+            (
+                "fn(...rest, x) {}",
+                "1:13: rest parameter must be the last parameter",
+            ),
+            (
+                "fn(...rest = []) {}",
+                "1:12: rest parameter cannot have a default value",
+            ),
+            (
+                "fn f(...[a]) {}",
+                "1:9: expected next token to be Ident, got LBracket instead",
+            ),
+        ];
+
+        // Act + Assert
+        assert_parse_error(tests);
+    }
+
+    #[test]
+    fn spread_outside_call_arguments_is_error() {
+        // Arrange
+        let tests = vec![("[...xs]", "1:2: spread is only allowed in call arguments")];
+
+        // Act + Assert
+        assert_parse_error(tests);
+    }
+
+    #[test]
+    fn invalid_patterns_are_errors() {
+        // Arrange
+        let tests = vec![
+            (
+                "match (x) { x + 1 => 2 }",
+                "1:15: expected next token to be FatArrow, got Plus instead",
+            ),
+            (
+                "match (x) { (1) => 2 }",
+                "1:13: expected a pattern, got LParen instead",
+            ),
+            (
+                "match (x) { [...a, b] => 2 }",
+                "1:20: rest pattern must be the last element",
+            ),
+            (
+                "match (x) { {k: v} => 2 }",
+                "1:14: expected a literal, got Ident instead",
+            ),
+            (
+                "match (x) { 1 => 2 3 => 4 }",
+                "1:20: expected next token to be Comma, got Int instead",
+            ),
+            (
+                "let 5 + 1 = x;",
+                "1:7: expected next token to be Assign, got Plus instead",
+            ),
+        ];
+
+        // Act + Assert
+        assert_parse_error(tests);
+    }
+
+    #[test]
+    fn export_outside_top_level_is_error() {
+        // Arrange
+        let tests = vec![
+            (
+                "fn f() { export let x = 1; }",
+                "1:10: export is only allowed at the top level of a module",
+            ),
+            (
+                "if (true) { export fn g() {} }",
+                "1:13: export is only allowed at the top level of a module",
+            ),
+        ];
+
+        // Act + Assert
+        assert_parse_error(tests);
+    }
+
+    #[test]
+    fn malformed_export_or_import_is_error() {
+        // Arrange
+        let tests = vec![
+            (
+                "export x + 1;",
+                "1:1: export must be followed by a let or fn declaration",
+            ),
+            (
+                "import utils as u;",
+                "1:8: expected next token to be Str, got Ident instead",
+            ),
+        ];
+
+        // Act + Assert
+        assert_parse_error(tests);
+    }
+
+    #[test]
+    fn try_without_handler_is_error() {
+        // Arrange
+        let tests = vec![
+            ("try { 1 }", "1:1: try needs a catch or finally block"),
+            (
+                "try { 1 } catch { 2 }",
+                "1:17: expected next token to be LParen, got LBrace instead",
+            ),
+        ];
+
+        // Act + Assert
+        assert_parse_error(tests);
+    }
+
+    #[test]
+    fn parsing_resumes_after_a_failed_statement() {
+        // Arrange
+        let tests = vec![
+            (
+                "let x = @; let y = ; let z = 1;",
+                vec![
+                    "1:9: unexpected character '@'",
+                    "1:20: can't parse expression that starts with SemiColon",
+                ],
+            ),
+            (
+                "let f = fn() { 1 + @; 2 }; f(",
+                vec![
+                    "1:20: unexpected character '@'",
+                    "1:30: can't parse expression that starts with Eof",
+                ],
+            ),
+            (
+                "if (x) { 1 + } let y = ;",
+                vec![
+                    "1:14: can't parse expression that starts with RBrace",
+                    "1:24: can't parse expression that starts with SemiColon",
+                ],
+            ),
+            (
+                "match (x) { x + 1 => { 2; } }; let y = @;",
+                vec![
+                    "1:15: expected next token to be FatArrow, got Plus instead",
+                    "1:40: unexpected character '@'",
+                ],
+            ),
+        ];
+
+        for (input, expected) in tests {
+            // Act
+            let lexer = Lexer::new(input);
+            let mut parser = Parser::new(lexer);
+            parser.parse_program();
+
+            // Assert
+            assert_eq!(parser.errors, expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn deep_nesting_is_an_error() {
+        // Arrange
+        let tests = vec![
+            ("[".repeat(100_000) + &"]".repeat(100_000), "1:1001"),
+            ("(".repeat(100_000) + "1" + &")".repeat(100_000), "1:1001"),
+            ("!".repeat(200_000) + "true", "1:1001"),
+            (
+                "if (true) { ".repeat(30_000) + &"}".repeat(30_000),
+                "1:6001",
+            ),
+            (
+                "let ".to_string() + &"[".repeat(5_000) + &"]".repeat(5_000) + " = 1;",
+                "1:1005",
+            ),
+        ];
+
+        for (input, position) in tests {
+            // Act
+            let lexer = Lexer::new(&input);
+            let mut parser = Parser::new(lexer);
+            parser.parse_program();
+
+            // Assert
+            let expected = format!("{position}: nesting is more than 1000 levels deep");
+            assert_eq!(parser.errors, vec![expected]);
+        }
+    }
+
+    #[test]
+    fn nesting_up_to_the_limit_parses() {
+        // Arrange
+        let tests = vec![
+            "[".repeat(999) + &"]".repeat(999),
+            "-".repeat(999) + "1",
+            "if (true) { ".repeat(499) + &"}".repeat(499),
+        ];
+
+        for input in tests {
+            // Act
+            let lexer = Lexer::new(&input);
+            let mut parser = Parser::new(lexer);
+            parser.parse_program();
+
+            // Assert
+            check_parser_errors(&parser);
+        }
+    }
+
+    #[test]
+    fn block_statements_take_an_optional_semicolon() {
+        // Arrange
+        let tests = vec![
+            ("while (false) {};", "while (false) {  }"),
+            ("while (false) {}; 1", "while (false) {  }1"),
+            ("for (x in xs) {}; 1", "for (x in xs) {  }1"),
+            ("fn f() {}; 1", "fn f() {  }1"),
+            ("fn f() {} 1", "fn f() {  }1"),
+        ];
+
+        for (input, expected) in tests {
+            // Act
+            let lexer = Lexer::new(input);
+            let mut parser = Parser::new(lexer);
+            let program = parser.parse_program();
+
+            // Assert
+            check_parser_errors(&parser);
+            assert_eq!(program.to_string(), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn while_statement() {
+        // Arrange
+        let input = "while (x < 10) { if (x == 5) { break; } continue; }";
+
+        // Act
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let program = parser.parse_program();
+
+        // Assert
+        check_parser_errors(&parser);
+        assert_eq!(program.statements.len(), 1);
+        match &program.statements[0] {
+            Statement::While {
+                condition, body, ..
+            } => {
+                assert_eq!(condition.to_string(), "(x < 10)");
+                assert_eq!(body.statements.len(), 2);
+                assert!(matches!(body.statements[1], Statement::Continue { .. }));
+            }
+            s => panic!("{s} is not a while statement"),
+        };
+    }
+
+    #[test]
+    fn break_and_continue_outside_loop() {
+        // Arrange
+        let tests = vec![
+            ("break;", "1:1: break outside of a loop"),
+            (
+                "if (true) { continue; }",
+                "1:13: continue outside of a loop",
+            ),
+            (
+                "while (true) { fn() { break; } }",
+                "1:23: break outside of a loop",
+            ),
+        ];
+
+        for (input, expected) in tests {
+            // Act
+            let lexer = Lexer::new(input);
+            let mut parser = Parser::new(lexer);
+            parser.parse_program();
+
+            // Assert
+            assert_eq!(parser.errors, vec![expected.to_string()], "{input}");
+        }
+    }
+
+    #[test]
+    fn array_literal_expression() {
+        // Arrange
+        let input = "[1, 2 * 2, 3 + 3]";
+
+        // Act
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let program = parser.parse_program();
+
+        // Assert
+        check_parser_errors(&parser);
+        match &program.statements[0] {
+            Statement::Expression {
+                span: _,
+                expression: Expression::ArrayLiteral { elements, .. },
+            } => {
+                assert_eq!(elements.len(), 3);
+                assert_eq!(elements[0].to_string(), "1");
+                assert_eq!(elements[1].to_string(), "(2 * 2)");
+                assert_eq!(elements[2].to_string(), "(3 + 3)");
+            }
+            s => panic!("{s} is not an array literal"),
+        };
+    }
+
+    #[test]
+    fn hash_literal_expression() {
+        // Arrange
+        let tests = vec![
+            ("{}", vec![]),
+            (
+                r#"{"one": 1, "two": 2 * 1, 3: true}"#,
+                vec![("\"one\"", "1"), ("\"two\"", "(2 * 1)"), ("3", "true")],
+            ),
+        ];
+
+        for (input, expected) in tests {
+            // Act
+            let lexer = Lexer::new(input);
+            let mut parser = Parser::new(lexer);
+            let program = parser.parse_program();
+
+            // Assert
+            check_parser_errors(&parser);
+            match &program.statements[0] {
+                Statement::Expression {
+                    span: _,
+                    expression: Expression::HashLiteral { pairs, .. },
+                } => {
+                    let pairs = pairs
+                        .iter()
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .collect::<Vec<_>>();
+                    let expected = expected
+                        .iter()
+                        .map(|(k, v)| (k.to_string(), v.to_string()))
+                        .collect::<Vec<_>>();
+                    assert_eq!(pairs, expected);
+                }
+                s => panic!("{s} is not a hash literal"),
+            };
+        }
+    }
+
+    #[test]
+    fn for_statement() {
+        // Arrange
+        let input = "for (x in 0..=10) { if (x > 5) { break; } x }";
+
+        // Act
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let program = parser.parse_program();
+
+        // Assert
+        check_parser_errors(&parser);
+        assert_eq!(program.statements.len(), 1);
+        match &program.statements[0] {
+            Statement::For {
+                variable,
+                iterable,
+                body,
+                ..
+            } => {
+                assert_eq!(variable.value, "x");
+                assert_eq!(iterable.to_string(), "(0..=10)");
+                assert_eq!(body.statements.len(), 2);
+            }
+            s => panic!("{s} is not a for statement"),
+        };
+    }
 }
@@ -1,33 +1,52 @@
 use std::io::{self, Stdin, Write};
 
+use crate::evaluator::{Capabilities, Evaluator};
 use crate::lexer::Lexer;
+use crate::object::ObjectType;
 use crate::parser::Parser;
 
 static PROMPT: &str = ">> ";
 
-pub fn start(stdin: &mut Stdin) {
+pub fn start(stdin: &mut Stdin, capabilities: Capabilities) {
     let mut input_buffer = String::new();
+    let mut evaluator = Evaluator::new();
+    evaluator.set_capabilities(capabilities);
 
     loop {
         print!("{PROMPT}");
         io::stdout().flush().unwrap();
 
         input_buffer.clear();
-        stdin
+        let read = stdin
             .read_line(&mut input_buffer)
             .expect("Could not read from stdin");
 
-        let line = input_buffer.clone();
-        let lexer = Lexer::new(line);
+        if read == 0 {
+            break;
+        }
+
+        let lexer = Lexer::new(&input_buffer);
         let mut parser = Parser::new(lexer);
         let program = parser.parse_program();
 
+        for warning in &parser.warnings {
+            println!("\twarning: {warning}");
+        }
+
         if !parser.errors.is_empty() {
             print_parser_errors(parser.errors);
             continue;
         }
 
-        println!("{program}");
+        match evaluator.eval_program(&program) {
+            Ok(value) => println!("{}", value.inspect()),
+            Err(error) => {
+                if !error.stack.is_empty() {
+                    println!("{}", error.traceback());
+                }
+                println!("ERROR: {error}");
+            }
+        }
     }
 }
 
@@ -1,35 +1,60 @@
+use std::fmt::Display;
+
 use phf::phf_map;
+use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum Kind {
-    Illegal,
+    Illegal(Reason),
     Eof,
 
     // Identifiers + Literals
     Ident,
     Int,
+    Float,
+    Str,
 
     // Operators
     Assign,
+    PlusAssign,
+    MinusAssign,
+    AsterixAssign,
+    SlashAssign,
     Plus,
     Minus,
     Asterix,
     Slash,
 
+    Percent,
+
     Bang,
     Lt,
     Gt,
+    LtEq,
+    GtEq,
     Eq,
     Ne,
+    And,
+    Or,
+
+    Dot,
+    DotDot,
+    DotDotEq,
+    DotDotDot,
+    Question,
+    FatArrow,
 
     // Delimiters
     Comma,
     SemiColon,
+    Colon,
 
     LParen,
     RParen,
     LBrace,
     RBrace,
+    LBracket,
+    RBracket,
 
     // Keywords
     Function,
@@ -39,6 +64,20 @@ pub enum Kind {
     If,
     Else,
     Return,
+    While,
+    Break,
+    Continue,
+    For,
+    In,
+    Mut,
+    Match,
+    Import,
+    Export,
+    As,
+    Try,
+    Catch,
+    Finally,
+    Throw,
 }
 
 static KEYWORDS: phf::Map<&'static str, Kind> = phf_map! {
@@ -49,17 +88,105 @@ static KEYWORDS: phf::Map<&'static str, Kind> = phf_map! {
     "if" => Kind::If,
     "else" => Kind::Else,
     "return" => Kind::Return,
+    "while" => Kind::While,
+    "break" => Kind::Break,
+    "continue" => Kind::Continue,
+    "for" => Kind::For,
+    "in" => Kind::In,
+    "mut" => Kind::Mut,
+    "match" => Kind::Match,
+    "import" => Kind::Import,
+    "export" => Kind::Export,
+    "as" => Kind::As,
+    "try" => Kind::Try,
+    "catch" => Kind::Catch,
+    "finally" => Kind::Finally,
+    "throw" => Kind::Throw,
 };
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct Token {
+/// Why the lexer could not produce a valid token.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Reason {
+    UnexpectedChar(char),
+    UnterminatedString,
+    IntegerOverflow,
+    InvalidDigit(char),
+    MissingDigits,
+    /// A `_` in a number literal that is not between two digits, as in
+    /// `1_`, `1__0` or `0x_ff`.
+    MisplacedSeparator,
+    InvalidEscape(char),
+}
+
+impl Display for Reason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reason::UnexpectedChar(c) => write!(f, "unexpected character '{c}'"),
+            Reason::UnterminatedString => write!(f, "unterminated string literal"),
+            Reason::IntegerOverflow => write!(f, "integer literal is too large"),
+            Reason::InvalidDigit(c) => write!(f, "invalid digit '{c}' in number literal"),
+            Reason::MissingDigits => write!(f, "number literal is missing digits"),
+            Reason::MisplacedSeparator => {
+                write!(f, "`_` in a number literal must be between two digits")
+            }
+            Reason::InvalidEscape(c) => write!(f, "invalid escape sequence '\\{c}'"),
+        }
+    }
+}
+
+/// Line and column (both starting at 1) of a token in the source text.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    pub fn new(line: usize, column: usize) -> Position {
+        Position { line, column }
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Byte range of a token or node in the source text.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Span {
+        Span { start, end }
+    }
+
+    pub fn to(self, other: Span) -> Span {
+        Span::new(self.start, other.end)
+    }
+}
+
+/// A token borrowing its literal from the source it was lexed from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Token<'a> {
     pub kind: Kind,
-    pub literal: String,
+    pub literal: &'a str,
+    pub span: Span,
+    pub position: Position,
 }
 
-impl Token {
-    pub fn new(kind: Kind, literal: String) -> Token {
-        Token { kind, literal }
+impl<'a> Token<'a> {
+    pub fn new(kind: Kind, literal: &'a str, span: Span, position: Position) -> Token<'a> {
+        Token {
+            kind,
+            literal,
+            span,
+            position,
+        }
     }
 }
 